@@ -0,0 +1,836 @@
+//! Wrappers that change how other iterators yield their partitions.
+
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+#[cfg(target_has_atomic = "64")]
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use {conjugate, is_partition_of, FromSet, IntoIter, Part, Partitions};
+
+/// Iterates over partitions with their parts in descending order.
+///
+/// This is made by `Partitions::descending`.
+pub struct Descending {
+    inner: Partitions,
+    out: Vec<usize>,
+}
+
+impl Descending {
+    #[inline]
+    pub(crate) fn new(n: usize) -> Descending {
+        Descending {
+            inner: Partitions::new(n),
+            out: Vec::with_capacity(n),
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        let x = self.inner.next()?;
+
+        self.out.clear();
+        self.out.extend(x.iter().rev());
+        Some(&self.out)
+    }
+}
+
+/// Iterates over partitions, checking each one as it goes.
+///
+/// This is made by `Partitions::checked`, and yields exactly what
+/// `Partitions` would, but in debug builds it asserts that each
+/// partition is a partition of the right number. In release builds
+/// it's no slower than `Partitions`.
+pub struct Checked {
+    inner: Partitions,
+    n: usize,
+}
+
+impl Checked {
+    #[inline]
+    pub(crate) fn new(n: usize) -> Checked {
+        Checked {
+            inner: Partitions::new(n),
+            n,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        let x = self.inner.next()?;
+        debug_assert!(is_partition_of(self.n, x), "{:?} isn't a partition of {}", x, self.n);
+        Some(x)
+    }
+}
+
+/// Iterates over partitions with their parts in descending order,
+/// padded with zeros so that they always have `n` parts.
+///
+/// This is made by `Partitions::padded`.
+pub struct Padded {
+    inner: Partitions,
+    out: Vec<usize>,
+}
+
+impl Padded {
+    #[inline]
+    pub(crate) fn new(n: usize) -> Padded {
+        Padded {
+            inner: Partitions::new(n),
+            out: vec![0; n],
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        let x = self.inner.next()?;
+
+        for (y, &z) in self.out.iter_mut().zip(x.iter().rev()) {
+            *y = z;
+        }
+        for y in &mut self.out[x.len()..] {
+            *y = 0;
+        }
+        Some(&self.out)
+    }
+}
+
+/// Iterates over partitions, counting them in a shared counter.
+///
+/// This is made by `Partitions::with_progress`, and yields exactly what
+/// `Partitions` would, but adds one to the counter for each partition,
+/// so that another thread can keep an eye on how far it's got.
+#[cfg(target_has_atomic = "64")]
+pub struct Progress {
+    inner: Partitions,
+    counter: Arc<AtomicU64>,
+}
+
+#[cfg(target_has_atomic = "64")]
+impl Progress {
+    #[inline]
+    pub(crate) fn new(n: usize, counter: Arc<AtomicU64>) -> Progress {
+        Progress {
+            inner: Partitions::new(n),
+            counter,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        let x = self.inner.next()?;
+        self.counter.fetch_add(1, Ordering::Relaxed);
+        Some(x)
+    }
+}
+
+/// Iterates over the partitions with an even or odd number of parts.
+///
+/// This is made by `Partitions::even_length` and `Partitions::odd_length`.
+/// The partitions come out in the same order as they would from
+/// `Partitions`, and the empty partition counts as even.
+pub struct Parity {
+    inner: Partitions,
+    parity: usize,
+}
+
+impl Parity {
+    #[inline]
+    pub(crate) fn new(n: usize, parity: usize) -> Parity {
+        Parity {
+            inner: Partitions::new(n),
+            parity,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        loop {
+            self.inner.next()?;
+            if self.inner.num_parts() & 1 == self.parity {
+                return Some(self.inner.current());
+            }
+        }
+    }
+}
+
+/// Iterates over partitions with a function applied to each part.
+///
+/// This is made by `Partitions::map_parts`. The mapped parts are kept
+/// in the same order, in one buffer that's reused for each partition.
+pub struct MapParts<T: Part, F> {
+    inner: Partitions<T>,
+    f: F,
+    out: Vec<usize>,
+}
+
+impl<T: Part, F: FnMut(usize) -> usize> MapParts<T, F> {
+    #[inline]
+    pub(crate) fn new(inner: Partitions<T>, f: F) -> MapParts<T, F> {
+        MapParts {
+            inner,
+            f,
+            out: Vec::new(),
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        let x = self.inner.next()?;
+        let f = &mut self.f;
+
+        self.out.clear();
+        self.out.extend(x.iter().map(|&y| f(y.to_usize())));
+        Some(&self.out)
+    }
+}
+
+/// Iterates over partitions as owned vectors, each paired with its
+/// index in the whole sequence.
+///
+/// This is made by `Partitions::enumerated`. The indices carry on from
+/// wherever the iterator was, so one made by `Partitions::from_cursor`
+/// or `Partitions::resume_after` counts from there, and each index is
+/// the rank of its partition, as `unrank` would take it.
+pub struct Enumerated<T: Part = usize> {
+    inner: Partitions<T>,
+}
+
+impl<T: Part> Enumerated<T> {
+    #[inline]
+    pub(crate) fn new(inner: Partitions<T>) -> Enumerated<T> {
+        assert!(inner.yielded.is_some(), "the position of the iterator isn't known");
+        Enumerated { inner }
+    }
+}
+
+impl<T: Part> Iterator for Enumerated<T> {
+    type Item = (u64, Vec<T>);
+
+    #[inline]
+    fn next(&mut self) -> Option<(u64, Vec<T>)> {
+        let i = self.inner.yielded.unwrap();
+        let x = self.inner.next()?;
+        Some((i, x.to_vec()))
+    }
+}
+
+impl<T: Part> FusedIterator for Enumerated<T> {}
+
+/// Iterates over partitions as lists of distinct parts, each with the
+/// number of times it occurs.
+///
+/// This is made by `Partitions::multiplicities`, and the parts are in
+/// ascending order.
+pub struct Multiplicities {
+    inner: Partitions,
+    out: Vec<(usize, usize)>,
+}
+
+impl Multiplicities {
+    #[inline]
+    pub(crate) fn new(n: usize) -> Multiplicities {
+        Multiplicities {
+            inner: Partitions::new(n),
+            out: Vec::new(),
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[(usize, usize)]> {
+        let x = self.inner.next()?;
+
+        self.out.clear();
+        for &y in x {
+            match self.out.last_mut() {
+                Some(&mut (part, ref mut count)) if part == y => *count += 1,
+                _ => self.out.push((y, 1)),
+            }
+        }
+
+        Some(&self.out)
+    }
+}
+
+/// Iterates over the self-conjugate partitions of a given integer.
+///
+/// This is made by `Partitions::self_conjugate`. Rather than checking
+/// every partition, each one is built up from hooks around the Durfee
+/// square, whose sizes form a partition into distinct odd parts. The
+/// parts are in ascending order, but the order of the partitions
+/// themselves is unspecified.
+pub struct SelfConjugate {
+    hooks: FromSet,
+    out: Vec<usize>,
+}
+
+impl SelfConjugate {
+    #[inline]
+    pub(crate) fn new(n: usize) -> SelfConjugate {
+        SelfConjugate {
+            hooks: FromSet::distinct_odd(n),
+            out: Vec::new(),
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        let hooks = self.hooks.next()?;
+        let d = hooks.len();
+
+        // The i-th hook, from the biggest, has an arm and a leg each of
+        // length (h - 1) / 2 around the i-th diagonal cell.
+        self.out.clear();
+        for (i, &h) in hooks.iter().rev().enumerate() {
+            self.out.push(i + 1 + (h - 1) / 2);
+        }
+
+        // The rest of the rows are just the columns below the square.
+        let top = self.out.first().cloned().unwrap_or(0);
+        for i in (d + 1)..(top + 1) {
+            let len = self.out[..d].iter().take_while(|&&x| x >= i).count();
+            self.out.push(len);
+        }
+
+        self.out.reverse();
+        Some(&self.out)
+    }
+}
+
+/// Iterates over the partitions of every integer up to some maximum,
+/// each with the integer it's a partition of.
+///
+/// This is made by `Partitions::all_up_to`. The integers go up from
+/// zero, and one buffer is recycled for all of them.
+pub struct AllUpTo {
+    inner: Option<Partitions>,
+    n: usize,
+    max: usize,
+}
+
+impl AllUpTo {
+    #[inline]
+    pub(crate) fn new(max: usize) -> AllUpTo {
+        AllUpTo {
+            inner: Some(Partitions::recycle(0, Vec::with_capacity(max + 1))),
+            n: 0,
+            max,
+        }
+    }
+}
+
+impl Iterator for AllUpTo {
+    type Item = (usize, Vec<usize>);
+
+    fn next(&mut self) -> Option<(usize, Vec<usize>)> {
+        loop {
+            let p = self.inner.as_mut()?;
+            if let Some(x) = p.next() {
+                return Some((self.n, x.to_vec()));
+            }
+
+            let vec = self.inner.take().unwrap().end();
+            if self.n < self.max {
+                self.n += 1;
+                self.inner = Some(Partitions::recycle(self.n, vec));
+            }
+        }
+    }
+}
+
+impl FusedIterator for AllUpTo {}
+
+/// Iterates over partitions as boxed slices, which have no spare
+/// capacity.
+///
+/// This is made by `Partitions::boxed_slices`, and the parts are in
+/// ascending order.
+pub struct BoxedSlices {
+    inner: Partitions,
+}
+
+impl BoxedSlices {
+    #[inline]
+    pub(crate) fn new(n: usize) -> BoxedSlices {
+        BoxedSlices {
+            inner: Partitions::new(n),
+        }
+    }
+}
+
+impl Iterator for BoxedSlices {
+    type Item = Box<[usize]>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Box<[usize]>> {
+        self.inner.next().map(Box::from)
+    }
+}
+
+impl FusedIterator for BoxedSlices {}
+
+/// Iterates over partitions as owned vectors, each paired with its
+/// conjugate.
+///
+/// This is made by `Partitions::with_conjugate`, and the parts of both
+/// are in ascending order.
+pub struct WithConjugate {
+    inner: IntoIter,
+}
+
+impl WithConjugate {
+    #[inline]
+    pub(crate) fn new(n: usize) -> WithConjugate {
+        WithConjugate {
+            inner: Partitions::new(n).into_iter(),
+        }
+    }
+}
+
+impl Iterator for WithConjugate {
+    type Item = (Vec<usize>, Vec<usize>);
+
+    #[inline]
+    fn next(&mut self) -> Option<(Vec<usize>, Vec<usize>)> {
+        let x = self.inner.next()?;
+        let y = conjugate(&x);
+        Some((x, y))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl FusedIterator for WithConjugate {}
+
+#[test]
+fn enumerated() {
+    //! Checks that the indices count up from the start, agree with
+    //! `unrank`, and carry on after resuming.
+
+    use {count, unrank, MAX_RANK};
+
+    for n in 0..20 {
+        for (i, (j, x)) in Partitions::new(n).enumerated().enumerate() {
+            assert_eq!(i as u64, j);
+            assert_eq!(unrank(n, j), Some(x));
+        }
+    }
+
+    let mut p = Partitions::new(12);
+    for _ in 0..30 {
+        p.next();
+    }
+    let (i, x) = p.enumerated().next().unwrap();
+    assert_eq!(i, 30);
+    assert_eq!(unrank(12, 30), Some(x.clone()));
+
+    let (i, x) = Partitions::resume_after(12, &x).unwrap().enumerated().next().unwrap();
+    assert_eq!(i, 31);
+    assert_eq!(unrank(12, 31), Some(x));
+
+    // The indices don't fit in 32 bits by the end.
+    let last = count(MAX_RANK) as u64 - 1;
+    let x = unrank(MAX_RANK, last - 1).unwrap();
+    let mut p = Partitions::resume_after(MAX_RANK, &x).unwrap().enumerated();
+    assert_eq!(p.next(), Some((last, vec![MAX_RANK])));
+    assert_eq!(p.next(), None);
+}
+
+/// Iterates over partitions as reference-counted slices, which reuse
+/// their buffers once nothing else is holding them.
+///
+/// This is made by `Partitions::shared`. The last slice of each length
+/// is kept, and if every other handle to it has been dropped by the
+/// time another partition of that length comes along, it's overwritten
+/// instead of allocating a new one.
+pub struct Shared {
+    inner: Partitions,
+    /// The last slice that was handed out with each length.
+    spare: Vec<Option<Rc<[usize]>>>,
+}
+
+impl Shared {
+    #[inline]
+    pub(crate) fn new(n: usize) -> Shared {
+        Shared {
+            inner: Partitions::new(n),
+            spare: vec![None; n + 1],
+        }
+    }
+}
+
+impl Iterator for Shared {
+    type Item = Rc<[usize]>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Rc<[usize]>> {
+        let x = self.inner.next()?;
+        let slot = &mut self.spare[x.len()];
+
+        if let Some(rc) = slot.as_mut() {
+            if let Some(buf) = Rc::get_mut(rc) {
+                buf.copy_from_slice(x);
+                return Some(rc.clone());
+            }
+        }
+
+        let rc: Rc<[usize]> = Rc::from(x);
+        *slot = Some(rc.clone());
+        Some(rc)
+    }
+}
+
+impl FusedIterator for Shared {}
+
+/// Iterates over partitions as owned vectors, each tagged with the
+/// number that it's a partition of.
+///
+/// This is made by `Partitions::tagged`, and is handy for keeping track
+/// of the partitions after chaining together the iterators for several
+/// numbers.
+pub struct Tagged {
+    inner: IntoIter,
+    n: usize,
+}
+
+impl Tagged {
+    #[inline]
+    pub(crate) fn new(n: usize) -> Tagged {
+        Tagged {
+            inner: Partitions::new(n).into_iter(),
+            n,
+        }
+    }
+}
+
+impl Iterator for Tagged {
+    type Item = (usize, Vec<usize>);
+
+    #[inline]
+    fn next(&mut self) -> Option<(usize, Vec<usize>)> {
+        let x = self.inner.next()?;
+        Some((self.n, x))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl FusedIterator for Tagged {}
+
+#[test]
+fn descending() {
+    //! Checks that the partitions are the usual ones, reversed.
+
+    let mut expected = Vec::new();
+    Partitions::new(15).for_each(|x| {
+        let mut x = x.to_vec();
+        x.reverse();
+        expected.push(x);
+    });
+
+    let mut actual = Vec::new();
+    let mut p = Descending::new(15);
+    while let Some(x) = p.next() {
+        assert!(x.windows(2).all(|w| w[0] >= w[1]));
+        actual.push(x.to_vec());
+    }
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn multiplicities() {
+    //! Checks that expanding the multiplicities gives the partitions.
+
+    let mut expected = Vec::new();
+    Partitions::new(15).for_each(|x| expected.push(x.to_vec()));
+
+    let mut actual = Vec::new();
+    let mut p = Multiplicities::new(15);
+    while let Some(x) = p.next() {
+        assert!(x.windows(2).all(|w| w[0].0 < w[1].0));
+
+        let mut flat = Vec::new();
+        for &(part, count) in x {
+            assert!(count > 0);
+            flat.extend((0..count).map(|_| part));
+        }
+        actual.push(flat);
+    }
+
+    assert_eq!(actual, expected);
+
+    let mut p = Multiplicities::new(0);
+    assert_eq!(p.next(), Some(&[][..]));
+    assert_eq!(p.next(), None);
+}
+
+#[test]
+fn self_conjugate() {
+    //! Checks the counts against A000700, and that each partition is
+    //! really its own conjugate.
+
+    let tests: &[usize] = &[
+        1, 1, 0, 1, 1, 1, 1, 1, 2, 2, 2,
+        2, 3, 3, 3, 4, 5, 5, 5, 6, 7,
+        8, 8, 9, 11, 12, 12, 14, 16, 17, 18,
+    ];
+
+    for (n, &c) in tests.iter().enumerate() {
+        let mut p = SelfConjugate::new(n);
+        let mut seen = Vec::new();
+
+        while let Some(x) = p.next() {
+            assert_eq!(x.iter().cloned().sum::<usize>(), n);
+            assert_eq!(::conjugate(x), x);
+            assert!(!seen.contains(&x.to_vec()));
+            seen.push(x.to_vec());
+        }
+
+        assert_eq!(seen.len(), c);
+    }
+}
+
+#[test]
+fn padded() {
+    //! Checks the lengths, and that the parts before the zeros are the
+    //! usual ones.
+
+    for n in 0..20 {
+        let mut expected = Partitions::new(n);
+        let mut p = Padded::new(n);
+
+        while let Some(x) = p.next() {
+            assert_eq!(x.len(), n);
+
+            let mut y = expected.next().unwrap().to_vec();
+            y.reverse();
+            assert_eq!(&x[..y.len()], &y[..]);
+            assert!(x[y.len()..].iter().all(|&z| z == 0));
+        }
+
+        assert_eq!(expected.next(), None);
+    }
+
+    assert_eq!(Padded::new(0).next(), Some(&[][..]));
+}
+
+#[cfg(target_has_atomic = "64")]
+#[test]
+fn progress() {
+    //! Checks that a watching thread sees the count go up to the total.
+
+    use count;
+    use std::thread;
+
+    let counter = Arc::new(AtomicU64::new(0));
+    let watched = counter.clone();
+    let watcher = thread::spawn(move || {
+        let mut last = 0;
+        while last < 204226 {
+            let now = watched.load(Ordering::Relaxed);
+            assert!(now >= last);
+            last = now;
+            thread::yield_now();
+        }
+        last
+    });
+
+    let mut p = Progress::new(50, counter.clone());
+    let mut c = 0;
+    while p.next().is_some() {
+        c += 1;
+    }
+
+    assert_eq!(c, count(50));
+    assert_eq!(counter.load(Ordering::Relaxed) as u128, count(50));
+    assert_eq!(watcher.join().unwrap(), 204226);
+}
+
+#[test]
+fn parity() {
+    //! Checks the lengths, and that the difference between the counts
+    //! is plus or minus the number of self-conjugate partitions, since
+    //! the product of `1 / (1 + q^k)` is the product of `1 - q^k` over
+    //! odd `k`.
+
+    for n in 0..30 {
+        let mut counts = Vec::new();
+        for parity in 0..2 {
+            let mut all = Vec::new();
+            let mut p = Parity::new(n, parity);
+            while let Some(x) = p.next() {
+                assert_eq!(x.len() & 1, parity);
+                all.push(x.to_vec());
+            }
+
+            let mut expected: Vec<Vec<usize>> = Partitions::new(n).into_iter().collect();
+            expected.retain(|x| x.len() & 1 == parity);
+            assert_eq!(all, expected);
+            counts.push(all.len() as i64);
+        }
+
+        let mut symmetric = 0;
+        let mut p = SelfConjugate::new(n);
+        while p.next().is_some() {
+            symmetric += 1;
+        }
+
+        let sign = if n & 1 == 0 { 1 } else { -1 };
+        assert_eq!(counts[0] - counts[1], sign * symmetric);
+    }
+
+    assert_eq!(Parity::new(0, 0).next(), Some(&[][..]));
+    assert_eq!(Parity::new(0, 1).next(), None);
+}
+
+#[test]
+fn map_parts() {
+    //! Checks that doubling the parts gives partitions of twice the
+    //! number.
+
+    for n in 0..15 {
+        let mut expected = Partitions::new(n);
+        let mut p = MapParts::new(Partitions::<u8>::of(n), |x| 2 * x);
+
+        while let Some(x) = p.next() {
+            let y: Vec<usize> = expected.next().unwrap().iter().map(|&z| 2 * z).collect();
+            assert_eq!(x, &y[..]);
+            assert_eq!(x.iter().sum::<usize>(), 2 * n);
+        }
+
+        assert_eq!(expected.next(), None);
+    }
+}
+
+#[test]
+fn checked() {
+    //! Checks that the checked iterator passes, and yields the usual
+    //! partitions.
+
+    for n in 0..51 {
+        let mut expected = Partitions::new(n);
+        let mut p = Checked::new(n);
+        while let Some(x) = p.next() {
+            assert_eq!(Some(x), expected.next());
+        }
+        assert_eq!(expected.next(), None);
+    }
+}
+
+#[test]
+fn all_up_to() {
+    //! Checks the sums, and that every partition is there in order.
+
+    use count;
+
+    let mut expected = Vec::new();
+    for n in 0..16 {
+        Partitions::new(n).for_each(|x| expected.push((n, x.to_vec())));
+    }
+
+    let all: Vec<(usize, Vec<usize>)> = AllUpTo::new(15).collect();
+    assert!(all.iter().all(|&(n, ref x)| x.iter().sum::<usize>() == n));
+    assert_eq!(all.len() as u128, (0..16).map(count).sum::<u128>());
+    assert_eq!(all, expected);
+
+    assert_eq!(AllUpTo::new(0).collect::<Vec<_>>(), [(0, vec![])]);
+}
+
+#[test]
+fn boxed_slices() {
+    //! Compares against the usual iterator.
+
+    let expected: Vec<Box<[usize]>> = Partitions::new(20)
+        .into_iter()
+        .map(|x| x.into_boxed_slice())
+        .collect();
+
+    assert_eq!(BoxedSlices::new(20).collect::<Vec<_>>(), expected);
+    assert_eq!(BoxedSlices::new(0).collect::<Vec<_>>(), [Box::from(&[][..])]);
+}
+
+#[test]
+fn with_conjugate() {
+    //! Checks each conjugate, and that self-conjugate ones match.
+
+    let mut symmetric = 0;
+    for (x, y) in WithConjugate::new(20) {
+        assert_eq!(y, conjugate(&x));
+        if x == y {
+            symmetric += 1;
+        }
+    }
+
+    // A000700 counts the self-conjugate partitions.
+    assert_eq!(symmetric, 7);
+    assert_eq!(WithConjugate::new(20).size_hint(), (627, Some(627)));
+    assert_eq!(WithConjugate::new(0).collect::<Vec<_>>(), [(vec![], vec![])]);
+}
+
+#[test]
+fn tagged() {
+    //! Checks the tags after chaining several numbers together.
+
+    let mut seen = vec![0; 15];
+    for (n, x) in (0..15).flat_map(Tagged::new) {
+        assert_eq!(x.iter().sum::<usize>(), n);
+        seen[n] += 1;
+    }
+
+    for (n, &c) in seen.iter().enumerate() {
+        assert_eq!(c, ::count(n));
+        assert_eq!(Tagged::new(n).size_hint().1, Some(c as usize));
+    }
+}
+
+#[test]
+fn shared() {
+    //! Checks that handles that are kept around don't change, and that
+    //! dropping each one straight away lets them all be reused.
+
+    use pool::counting;
+
+    let expected: Vec<Vec<usize>> = Partitions::new(20).into_iter().collect();
+
+    let kept: Vec<Rc<[usize]>> = Shared::new(20).collect();
+    assert_eq!(kept.len(), expected.len());
+    for (x, y) in kept.iter().zip(&expected) {
+        assert_eq!(&x[..], &y[..]);
+    }
+
+    // There's at most one allocation for each length.
+    let mut p = Shared::new(20);
+    let before = counting::allocations();
+    for y in &expected {
+        let x = p.next().unwrap();
+        assert_eq!(&x[..], &y[..]);
+    }
+    assert_eq!(p.next(), None);
+    assert!(counting::allocations() - before <= 20);
+}
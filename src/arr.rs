@@ -0,0 +1,72 @@
+//! Iteration without any allocations at all.
+
+use {step, State};
+
+/// Iterates over the partitions of a number up to `N`, keeping the
+/// parts in an array rather than on the heap.
+///
+/// Since the iterator owns no heap memory, each partition is copied
+/// out into a buffer that the caller provides.
+pub struct PartitionsArr<const N: usize> {
+    a: [usize; N],
+    k: usize,
+    y: usize,
+    next: State,
+}
+
+impl<const N: usize> PartitionsArr<N> {
+    /// Makes a new iterator over the partitions of `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n > N`.
+    #[inline]
+    pub fn new(n: usize) -> PartitionsArr<N> {
+        assert!(n <= N, "{} doesn't fit in an array of {}", n, N);
+
+        PartitionsArr {
+            a: [0; N],
+            k: if n == 0 { 0 } else { 1 },
+            y: if n == 0 { 0 } else { n - 1 },
+            next: if n == 0 { State::EmptyOnce } else { State::A },
+        }
+    }
+
+    /// Copies the next partition into the start of `out`, and returns
+    /// how many parts it has.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is too short for the partition, which can't
+    /// happen if it's at least `n` long.
+    #[inline]
+    pub fn next_into(&mut self, out: &mut [usize]) -> Option<usize> {
+        let len = step(&mut self.a, &mut self.k, &mut self.y, &mut self.next)?;
+        out[..len].copy_from_slice(&self.a[..len]);
+        Some(len)
+    }
+}
+
+#[test]
+fn same() {
+    //! Compares against the usual iterator.
+
+    fn check<const N: usize>(n: usize) {
+        let mut expected = ::Partitions::new(n);
+        let mut p = PartitionsArr::<N>::new(n);
+        let mut out = [0; N];
+
+        while let Some(len) = p.next_into(&mut out) {
+            assert_eq!(Some(&out[..len]), expected.next());
+        }
+        assert_eq!(expected.next(), None);
+        assert_eq!(p.next_into(&mut out), None);
+    }
+
+    for n in 0..9 {
+        check::<8>(n);
+    }
+    check::<0>(0);
+    check::<1>(1);
+    check::<20>(20);
+}
@@ -0,0 +1,154 @@
+//! Compositions, which are like partitions but with ordered parts.
+
+use alloc::vec::Vec;
+
+/// Iterates over the compositions of a given integer, which are the
+/// ways of writing it as a sum of positive parts in some order.
+///
+/// The compositions come out in lexicographic order, starting with as
+/// many ones as possible. There are `2^(n - 1)` of them for positive
+/// `n`, or `C(n - 1, k - 1)` of them into exactly `k` parts.
+pub struct Compositions {
+    a: Vec<usize>,
+    n: usize,
+    /// Whether the number of parts is fixed.
+    fixed: bool,
+    state: State,
+}
+
+enum State {
+    Start,
+    Running,
+    Done,
+}
+
+impl Compositions {
+    /// Makes an iterator over the compositions of `n`.
+    pub fn new(n: usize) -> Compositions {
+        Compositions {
+            a: vec![1; n],
+            n,
+            fixed: false,
+            state: State::Start,
+        }
+    }
+
+    /// Makes an iterator over the compositions of `n` into exactly `k`
+    /// parts.
+    pub fn into_parts(n: usize, k: usize) -> Compositions {
+        let possible = k <= n && (k == 0) == (n == 0);
+
+        let mut a = vec![1; if possible { k } else { 0 }];
+        if let Some(last) = a.last_mut() {
+            *last = n + 1 - k;
+        }
+
+        Compositions {
+            a,
+            n,
+            fixed: true,
+            state: if possible { State::Start } else { State::Done },
+        }
+    }
+}
+
+impl Iterator for Compositions {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        match self.state {
+            State::Start => self.state = State::Running,
+            State::Running if self.fixed => {
+                // The last part above one gives a unit to the part before
+                // it, and then everything after that is as small as it
+                // can be, so all but the last part are ones.
+                let j = match self.a.iter().rposition(|&x| x > 1) {
+                    Some(j) if j > 0 => j,
+                    _ => {
+                        self.state = State::Done;
+                        return None;
+                    },
+                };
+
+                let len = self.a.len();
+                let t = self.a[j];
+                self.a[j - 1] += 1;
+                self.a[j] = 1;
+                self.a[len - 1] = t - 1;
+            },
+            State::Running => {
+                // One unit of the last part moves to the part before it,
+                // and the rest of it is split back off as ones.
+                if self.a.len() < 2 {
+                    self.state = State::Done;
+                    return None;
+                }
+
+                let last = self.a.pop().unwrap();
+                *self.a.last_mut().unwrap() += 1;
+                for _ in 1..last {
+                    self.a.push(1);
+                }
+            },
+            State::Done => return None,
+        }
+
+        debug_assert_eq!(self.a.iter().sum::<usize>(), self.n);
+        Some(self.a.clone())
+    }
+}
+
+#[test]
+fn small() {
+    //! Checks a small case by hand, and the counts and sums.
+
+    assert_eq!(Compositions::new(0).collect::<Vec<_>>(), [Vec::<usize>::new()]);
+    assert_eq!(
+        Compositions::new(3).collect::<Vec<_>>(),
+        [&[1, 1, 1][..], &[1, 2], &[2, 1], &[3]],
+    );
+
+    for n in 1..16 {
+        let all: Vec<Vec<usize>> = Compositions::new(n).collect();
+        assert_eq!(all.len(), 1 << (n - 1));
+        assert!(all.iter().all(|x| x.iter().all(|&y| y > 0) && x.iter().sum::<usize>() == n));
+        assert!(all.windows(2).all(|w| w[0] < w[1]));
+    }
+}
+
+#[test]
+fn into_parts() {
+    //! Checks the counts against the binomial coefficients, and that
+    //! the compositions are exactly the ones with the right length.
+
+    assert_eq!(Compositions::into_parts(0, 0).collect::<Vec<_>>(), [Vec::<usize>::new()]);
+    assert_eq!(
+        Compositions::into_parts(5, 3).collect::<Vec<_>>(),
+        [
+            &[1, 1, 3][..], &[1, 2, 2], &[1, 3, 1], &[2, 1, 2], &[2, 2, 1], &[3, 1, 1],
+        ],
+    );
+
+    for n in 0..14 {
+        let all: Vec<Vec<usize>> = Compositions::new(n).collect();
+
+        // `binomial[k]` is `C(n - 1, k - 1)`, except at zero.
+        let mut binomial = vec![0u64; n + 2];
+        binomial[if n == 0 { 0 } else { 1 }] = 1;
+        for k in 1..n {
+            binomial[k + 1] = binomial[k] * (n - k) as u64 / k as u64;
+        }
+
+        for (k, &c) in binomial.iter().enumerate() {
+            let mut expected = all.clone();
+            expected.retain(|x| x.len() == k);
+
+            let actual: Vec<Vec<usize>> = Compositions::into_parts(n, k).collect();
+            assert_eq!(actual.len() as u64, c);
+            assert_eq!(actual, expected);
+        }
+    }
+
+    assert_eq!(Compositions::into_parts(2, 5).next(), None);
+    assert_eq!(Compositions::into_parts(2, 0).next(), None);
+}
@@ -0,0 +1,612 @@
+//! Counting partitions without enumerating them.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "bignum")]
+use num_bigint::BigUint;
+
+/// The largest `n` for which `count(n)` fits in a `u128`.
+pub const MAX_COUNT: usize = 1458;
+
+/// Finds the number of partitions of `n`.
+///
+/// This uses Euler's pentagonal number recurrence, so it takes
+/// `O(n^1.5)` time rather than being proportional to the number of
+/// partitions.
+///
+/// # Panics
+///
+/// Panics if `n > MAX_COUNT`, since the answer would overflow.
+pub fn count(n: usize) -> u128 {
+    assert!(n <= MAX_COUNT, "the number of partitions of {} overflows a u128", n);
+    table(n)[n]
+}
+
+/// Finds the number of partitions of `n` modulo `m`.
+///
+/// This uses the same recurrence as `count`, but reduces everything
+/// modulo `m` as it goes along, so it works for any `n`.
+///
+/// # Panics
+///
+/// Panics if `m` is zero.
+pub fn count_mod(n: usize, m: u64) -> u64 {
+    assert!(m != 0, "can't count modulo zero");
+
+    let m = m as u128;
+    let mut p = vec![0u64; n + 1];
+    p[0] = (1 % m) as u64;
+
+    for i in 1..(n + 1) {
+        let mut sum = 0u128;
+
+        for k in 1.. {
+            let g = k * (3 * k - 1) / 2;
+            if g > i {
+                break;
+            }
+
+            let mut term = p[i - g] as u128;
+            if g + k <= i {
+                term = (term + p[i - g - k] as u128) % m;
+            }
+
+            sum = if k % 2 == 1 {
+                (sum + term) % m
+            } else {
+                (sum + m - term) % m
+            };
+        }
+
+        p[i] = sum as u64;
+    }
+
+    p[n]
+}
+
+/// Finds the number of partitions of `n` into exactly `k` parts.
+///
+/// This uses the recurrence `p(n, k) = p(n - 1, k - 1) + p(n - k, k)`,
+/// so it takes `O(nk)` time.
+///
+/// # Panics
+///
+/// Panics if the answer would overflow a `u128`.
+pub fn count_k(n: usize, k: usize) -> u128 {
+    if k > n {
+        return 0;
+    }
+
+    let mut last = if k == 0 && n == 0 { 1 } else { 0 };
+    each_k(n, k, |_, c| last = c);
+    last
+}
+
+/// Finds the number of partitions of `n` into at most `k` parts.
+///
+/// This adds up `count_k(n, j)` for each `j` up to `k`, so it takes
+/// `O(nk)` time.
+///
+/// # Panics
+///
+/// Panics if the answer would overflow a `u128`.
+pub fn count_at_most_parts(n: usize, k: usize) -> u128 {
+    let mut sum = if n == 0 { 1u128 } else { 0 };
+    each_k(n, k.min(n), |_, c| {
+        sum = sum
+            .checked_add(c)
+            .unwrap_or_else(|| panic!("the number of partitions of {} overflows a u128", n));
+    });
+    sum
+}
+
+/// Finds the number of partitions of `n` with no part bigger than `m`.
+///
+/// This is the coin-changing recurrence with the parts from 1 to `m`,
+/// so it takes `O(nm)` time. By conjugacy, it's always the same as
+/// `count_at_most_parts(n, m)`.
+///
+/// # Panics
+///
+/// Panics if the answer would overflow a `u128`.
+pub fn count_max_part(n: usize, m: usize) -> u128 {
+    let mut ways = vec![0u128; n + 1];
+    ways[0] = 1;
+
+    for x in 1..(m.min(n) + 1) {
+        for r in x..(n + 1) {
+            ways[r] = ways[r]
+                .checked_add(ways[r - x])
+                .unwrap_or_else(|| panic!("the number of partitions of {} overflows a u128", r));
+        }
+    }
+
+    ways[n]
+}
+
+/// Finds the number of partitions of `n` into distinct parts.
+///
+/// Each part can be used at most once, so this is the coin-changing
+/// recurrence with the sums going downwards, and it takes `O(n^2)`
+/// time.
+///
+/// # Panics
+///
+/// Panics if the answer would overflow a `u128`.
+pub fn count_distinct(n: usize) -> u128 {
+    let mut ways = vec![0u128; n + 1];
+    ways[0] = 1;
+
+    for x in 1..(n + 1) {
+        for r in (x..(n + 1)).rev() {
+            ways[r] = ways[r]
+                .checked_add(ways[r - x])
+                .unwrap_or_else(|| panic!("the number of partitions of {} overflows a u128", r));
+        }
+    }
+
+    ways[n]
+}
+
+/// Finds the number of partitions of `n` into exactly `k` distinct
+/// parts.
+///
+/// Taking `k - 1, k - 2, ..., 0` away from the parts, from the largest
+/// down, leaves a partition of `n - k(k - 1)/2` into exactly `k` parts
+/// that don't have to be distinct, so this is just `count_k` of that.
+///
+/// # Panics
+///
+/// Panics if the answer would overflow a `u128`.
+pub fn count_distinct_k(n: usize, k: usize) -> u128 {
+    // The staircase of `k` parts has `k(k + 1)/2` cells, and if that
+    // doesn't fit then neither does anything bigger.
+    let staircase = k.checked_add(1).and_then(|x| x.checked_mul(k)).map(|x| x / 2);
+    match staircase {
+        Some(s) if s <= n => count_k(n - s + k, k),
+        _ => 0,
+    }
+}
+
+/// Finds the number of partitions of `n` whose parts all come from
+/// `parts`, which can be in any order and contain duplicates.
+///
+/// This is the usual coin-changing recurrence, so it takes `O(nm)` time
+/// for `m` different parts.
+///
+/// # Panics
+///
+/// Panics if the answer would overflow a `u128`.
+pub fn restricted_count(n: usize, parts: &[usize]) -> u128 {
+    let mut set: Vec<usize> = parts.iter().cloned().filter(|&x| x > 0 && x <= n).collect();
+    set.sort();
+    set.dedup();
+
+    // After each part, `ways[r]` counts the partitions of `r` into the
+    // parts so far.
+    let mut ways = vec![0u128; n + 1];
+    ways[0] = 1;
+
+    for x in set {
+        for r in x..(n + 1) {
+            ways[r] = ways[r]
+                .checked_add(ways[r - x])
+                .unwrap_or_else(|| panic!("the number of partitions of {} overflows a u128", r));
+        }
+    }
+
+    ways[n]
+}
+
+/// Finds how many partitions of `n` there are with each number of
+/// parts, so the `k`th entry is `count_k(n, k)`.
+///
+/// This takes `O(n^2)` time, like a single call to `count_k(n, n)`.
+///
+/// # Panics
+///
+/// Panics if any of the counts would overflow a `u128`.
+pub fn length_histogram(n: usize) -> Vec<u128> {
+    let mut out = vec![0; n + 1];
+    out[0] = if n == 0 { 1 } else { 0 };
+    each_k(n, n, |j, c| out[j] = c);
+    out
+}
+
+/// Finds how many partitions of `n` there are with each largest part,
+/// so the `m`th entry counts the ones whose biggest part is exactly `m`.
+///
+/// Conjugating a partition swaps its largest part with its number of
+/// parts, so this is the same as `length_histogram`.
+///
+/// # Panics
+///
+/// Panics if any of the counts would overflow a `u128`.
+#[inline]
+pub fn largest_part_distribution(n: usize) -> Vec<u128> {
+    length_histogram(n)
+}
+
+/// Calls `f(j, count_k(n, j))` for each `j` from 1 to `k`.
+fn each_k<F: FnMut(usize, u128)>(n: usize, k: usize, mut f: F) {
+    // `row[m]` is the number of partitions of `m` into exactly `j`
+    // parts, for each `j` in turn.
+    let mut row = vec![0u128; n + 1];
+    row[0] = 1;
+
+    for j in 1..(k + 1) {
+        let mut next = vec![0u128; n + 1];
+        for m in j..(n + 1) {
+            next[m] = row[m - 1]
+                .checked_add(next[m - j])
+                .unwrap_or_else(|| panic!("the number of partitions of {} into {} parts overflows a u128", m, j));
+        }
+        row = next;
+        f(j, row[n]);
+    }
+}
+
+/// A table of partition counts, for answering lots of queries quickly.
+pub struct PartitionTable {
+    max: usize,
+    p: Vec<u128>,
+    /// The number of partitions of `n` into exactly `k` parts is at
+    /// `n * (n + 1) / 2 + k`.
+    tri: Vec<u128>,
+}
+
+impl PartitionTable {
+    /// Makes a table of the counts for every integer up to `max`.
+    ///
+    /// This takes `O(max^2)` time and space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max > MAX_COUNT`, since the counts would overflow.
+    pub fn up_to(max: usize) -> PartitionTable {
+        assert!(max <= MAX_COUNT, "the number of partitions of {} overflows a u128", max);
+
+        let mut tri = vec![0u128; (max + 1) * (max + 2) / 2];
+        tri[0] = 1;
+
+        for n in 1..(max + 1) {
+            let row = n * (n + 1) / 2;
+            for k in 1..(n + 1) {
+                let a = tri[(n - 1) * n / 2 + k - 1];
+                let m = n - k;
+                let b = if k <= m { tri[m * (m + 1) / 2 + k] } else { 0 };
+                tri[row + k] = a + b;
+            }
+        }
+
+        PartitionTable { max, p: table(max), tri }
+    }
+
+    /// Finds the number of partitions of `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is past the end of the table.
+    #[inline]
+    pub fn count(&self, n: usize) -> u128 {
+        assert!(n <= self.max, "{} is past the end of the table", n);
+        self.p[n]
+    }
+
+    /// Finds the number of partitions of `n` into exactly `k` parts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is past the end of the table.
+    #[inline]
+    pub fn count_k(&self, n: usize, k: usize) -> u128 {
+        assert!(n <= self.max, "{} is past the end of the table", n);
+        if k > n {
+            0
+        } else {
+            self.tri[n * (n + 1) / 2 + k]
+        }
+    }
+}
+
+/// Finds the number of partitions of every integer up to `n`.
+///
+/// The entries past `MAX_COUNT` are only correct modulo `2^128`.
+pub(crate) fn table(n: usize) -> Vec<u128> {
+    let mut p = vec![0u128; n + 1];
+    p[0] = 1;
+
+    for i in 1..(n + 1) {
+        // The partial sums can overflow even when the result doesn't,
+        // but since the arithmetic wraps they still come out right.
+        let mut sum = 0u128;
+
+        for k in 1.. {
+            let g = k * (3 * k - 1) / 2;
+            if g > i {
+                break;
+            }
+
+            let mut term = p[i - g];
+            if g + k <= i {
+                term = term.wrapping_add(p[i - g - k]);
+            }
+
+            sum = if k % 2 == 1 {
+                sum.wrapping_add(term)
+            } else {
+                sum.wrapping_sub(term)
+            };
+        }
+
+        p[i] = sum;
+    }
+
+    p
+}
+
+/// Finds the number of partitions of `n`, however large it is.
+///
+/// This uses the same recurrence as `count`, but with arbitrary
+/// precision arithmetic, so it never overflows.
+#[cfg(feature = "bignum")]
+pub fn count_big(n: usize) -> BigUint {
+    let mut p: Vec<BigUint> = Vec::with_capacity(n + 1);
+    p.push(BigUint::from(1u32));
+
+    for i in 1..(n + 1) {
+        // The terms are added and subtracted separately, since the
+        // running sum could otherwise go negative.
+        let mut plus = BigUint::from(0u32);
+        let mut minus = BigUint::from(0u32);
+
+        for k in 1.. {
+            let g = k * (3 * k - 1) / 2;
+            if g > i {
+                break;
+            }
+
+            let sum = if k % 2 == 1 { &mut plus } else { &mut minus };
+            *sum += &p[i - g];
+            if g + k <= i {
+                *sum += &p[i - g - k];
+            }
+        }
+
+        p.push(plus - minus);
+    }
+
+    p.pop().unwrap()
+}
+
+#[test]
+fn small() {
+    //! Compares against the enumerated counts.
+
+    for n in 0..50 {
+        let mut p = ::Partitions::new(n);
+        let mut c = 0;
+        while p.next().is_some() {
+            c += 1;
+        }
+
+        assert_eq!(count(n), c);
+    }
+}
+
+#[test]
+fn large() {
+    //! Checks some known values, including the largest one.
+
+    assert_eq!(count(1000), 24061467864032622473692149727991);
+    assert_eq!(count(MAX_COUNT), 336988065393447621514574974879775699372);
+}
+
+#[test]
+#[should_panic]
+fn overflow() {
+    //! Checks that an overflowing count is caught.
+
+    count(MAX_COUNT + 1);
+}
+
+#[test]
+fn modular() {
+    //! Checks Ramanujan's congruences, and compares against `count`.
+
+    for k in 0..21 {
+        assert_eq!(count_mod(5 * k + 4, 5), 0);
+        assert_eq!(count_mod(7 * k + 5, 7), 0);
+        assert_eq!(count_mod(11 * k + 6, 11), 0);
+    }
+
+    for n in 0..200 {
+        for &m in &[1, 2, 3, 10, 1_000_000_007, u64::MAX] {
+            assert_eq!(count_mod(n, m) as u128, count(n) % m as u128);
+        }
+    }
+}
+
+#[test]
+fn by_parts() {
+    //! Checks the edge cases, and that the counts add up to `count`.
+
+    assert_eq!(count_k(0, 0), 1);
+    assert_eq!(count_k(5, 0), 0);
+    assert_eq!(count_k(3, 4), 0);
+    assert_eq!(count_k(10, 3), 8);
+
+    for n in 0..51 {
+        let sum: u128 = (0..(n + 1)).map(|k| count_k(n, k)).sum();
+        assert_eq!(sum, count(n));
+    }
+}
+
+#[test]
+fn bounded() {
+    //! Compares against the enumerated counts, and checks that the two
+    //! counts agree, as conjugacy says they should.
+
+    for n in 0..30 {
+        for k in 0..(n + 2) {
+            let mut p = ::Partitions::at_most_parts(n, k);
+            let mut c = 0;
+            while p.next().is_some() {
+                c += 1;
+            }
+            assert_eq!(count_at_most_parts(n, k), c);
+
+            let mut p = ::Partitions::with_max_part(n, k);
+            let mut c = 0;
+            while p.next().is_some() {
+                c += 1;
+            }
+            assert_eq!(count_max_part(n, k), c);
+        }
+    }
+
+    for n in 0..100 {
+        for k in 0..(n + 2) {
+            assert_eq!(count_at_most_parts(n, k), count_max_part(n, k));
+        }
+        assert_eq!(count_max_part(n, n), count(n));
+    }
+}
+
+#[test]
+fn distinct() {
+    //! Compares against the enumerated counts, and checks some known
+    //! values from A000009.
+
+    for n in 0..50 {
+        let mut p = ::Partitions::distinct(n);
+        let mut c = 0;
+        while p.next().is_some() {
+            c += 1;
+        }
+
+        assert_eq!(count_distinct(n), c);
+    }
+
+    assert_eq!(count_distinct(0), 1);
+    assert_eq!(count_distinct(100), 444793);
+    assert_eq!(count_distinct(1000), 8635565795744155161506);
+}
+
+#[test]
+fn distinct_by_parts() {
+    //! Compares against the enumerated counts, and checks that the
+    //! counts add up to `count_distinct`.
+
+    for n in 0..40 {
+        let mut expected = vec![0u128; n + 2];
+        let mut p = ::Partitions::distinct(n);
+        while let Some(x) = p.next() {
+            expected[x.len()] += 1;
+        }
+
+        for (k, &c) in expected.iter().enumerate() {
+            assert_eq!(count_distinct_k(n, k), c);
+        }
+
+        let sum: u128 = (0..(n + 1)).map(|k| count_distinct_k(n, k)).sum();
+        assert_eq!(sum, count_distinct(n));
+    }
+
+    assert_eq!(count_distinct_k(0, 0), 1);
+    assert_eq!(count_distinct_k(5, 3), 0);
+    assert_eq!(count_distinct_k(6, 3), 1);
+    assert_eq!(count_distinct_k(10, usize::MAX), 0);
+}
+
+#[test]
+fn restricted() {
+    //! Compares against the enumerated counts, and checks that any part
+    //! gives `count`.
+
+    let sets: &[&[usize]] = &[
+        &[], &[1], &[2], &[1, 2], &[3, 5, 7], &[2, 2, 0, 9, 4], &[1, 5, 10, 25],
+    ];
+
+    for n in 0..40 {
+        for parts in sets {
+            let mut p = ::Partitions::from_parts(n, parts);
+            let mut c = 0;
+            while p.next().is_some() {
+                c += 1;
+            }
+
+            assert_eq!(restricted_count(n, parts), c);
+        }
+
+        let all: Vec<usize> = (1..(n + 1)).collect();
+        assert_eq!(restricted_count(n, &all), count(n));
+    }
+
+    assert_eq!(restricted_count(0, &[]), 1);
+    assert_eq!(restricted_count(5, &[]), 0);
+    assert_eq!(restricted_count(100, &[1, 5, 10, 25, 50, 100]), 293);
+}
+
+#[test]
+fn histogram() {
+    //! Checks that the histogram agrees with `count_k`, and adds up to
+    //! `count`.
+
+    for n in 0..60 {
+        let h = length_histogram(n);
+        assert_eq!(h.len(), n + 1);
+        assert_eq!(h.iter().sum::<u128>(), count(n));
+
+        for (k, &c) in h.iter().enumerate() {
+            assert_eq!(c, count_k(n, k));
+        }
+    }
+
+    assert_eq!(length_histogram(0), [1]);
+    assert_eq!(length_histogram(4), [0, 1, 2, 1, 1]);
+}
+
+#[test]
+fn largest_parts() {
+    //! Compares against tallying the largest parts of the enumerated
+    //! partitions, and checks that it adds up to `count`.
+
+    for n in 0..40 {
+        let mut expected = vec![0u128; n + 1];
+        ::Partitions::new(n).for_each(|x| expected[x.last().cloned().unwrap_or(0)] += 1);
+
+        let d = largest_part_distribution(n);
+        assert_eq!(d, expected);
+        assert_eq!(d.iter().sum::<u128>(), count(n));
+    }
+}
+
+#[test]
+fn partition_table() {
+    //! Compares the table against the standalone functions.
+
+    let t = PartitionTable::up_to(100);
+    for n in 0..101 {
+        assert_eq!(t.count(n), count(n));
+        for k in 0..(n + 2) {
+            assert_eq!(t.count_k(n, k), count_k(n, k));
+        }
+    }
+}
+
+#[cfg(feature = "bignum")]
+#[test]
+fn big() {
+    //! Compares against `count`, and checks a known larger value.
+
+    for n in 0..200 {
+        assert_eq!(count_big(n), BigUint::from(count(n)));
+    }
+
+    let expected: BigUint = "24061467864032622473692149727991".parse().unwrap();
+    assert_eq!(count_big(1000), expected);
+
+    let expected: BigUint = "496025142797537184410324879054927095334462742231683423624".parse().unwrap();
+    assert_eq!(count_big(3000), expected);
+}
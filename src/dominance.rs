@@ -0,0 +1,252 @@
+//! Partitions compared by dominance.
+
+use alloc::vec::Vec;
+
+/// Iterates over the partitions that dominate a given one.
+///
+/// This is made by `Partitions::dominating`. The parts of each
+/// partition are in descending order, and the partitions come out in
+/// reverse lexicographic order, like `Lexicographic`, but any prefix
+/// whose sums fall too far behind the base is never extended.
+pub struct Dominating {
+    a: Vec<usize>,
+    /// The sums of the largest parts of the base.
+    b: Vec<usize>,
+    n: usize,
+    state: State,
+}
+
+enum State {
+    Start,
+    Running,
+    Done,
+}
+
+impl Dominating {
+    #[inline]
+    pub(crate) fn new(n: usize, base: &[usize]) -> Dominating {
+        let mut sorted: Vec<usize> = base.iter().cloned().filter(|&x| x > 0).collect();
+        sorted.sort_by(|x, y| y.cmp(x));
+
+        let mut b = Vec::with_capacity(sorted.len());
+        let mut sum = 0;
+        for x in sorted {
+            sum += x;
+            b.push(sum);
+        }
+
+        Dominating {
+            a: Vec::with_capacity(b.len()),
+            state: if sum == n { State::Start } else { State::Done },
+            b,
+            n,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        match self.state {
+            State::Start => {
+                self.state = State::Running;
+                self.fill(0);
+            },
+            State::Running => {
+                // The last part that can shrink by one without falling
+                // behind does, and everything after it is packed back
+                // in as tightly as possible.
+                let mut s = self.n;
+                let mut i = self.a.len();
+
+                loop {
+                    if i == 0 {
+                        self.state = State::Done;
+                        return None;
+                    }
+
+                    i -= 1;
+                    s -= self.a[i];
+
+                    let x = self.a[i];
+                    if x > 1 && self.feasible(i + 1, s + x - 1, x - 1) {
+                        self.a.truncate(i);
+                        self.a.push(x - 1);
+                        self.fill(s + x - 1);
+                        break;
+                    }
+                }
+            },
+            State::Done => return None,
+        }
+
+        Some(&self.a)
+    }
+
+    /// Checks whether `j` parts that add up to `s` can be followed by
+    /// parts no bigger than `x` to make a dominating partition.
+    fn feasible(&self, j: usize, s: usize, x: usize) -> bool {
+        // The largest sums come from making each part as big as it can
+        // be, so they're the only ones that need checking.
+        for t in j..(self.b.len() + 1) {
+            let most = s + (t - j) * x;
+            if most >= self.n {
+                return true;
+            }
+            if t >= 1 && most < self.b[t - 1] {
+                return false;
+            }
+        }
+
+        false
+    }
+
+    /// Appends the largest parts that fit after the current ones, which
+    /// add up to `s`.
+    fn fill(&mut self, mut s: usize) {
+        let mut x = self.a.last().cloned().unwrap_or(self.n);
+        while s < self.n {
+            x = x.min(self.n - s);
+            self.a.push(x);
+            s += x;
+        }
+    }
+}
+
+/// Returns the partition of `n` that dominates all of the others,
+/// which has a single part.
+#[inline]
+pub fn dominance_top(n: usize) -> Vec<usize> {
+    if n == 0 {
+        Vec::new()
+    } else {
+        vec![n]
+    }
+}
+
+/// Returns the partition of `n` that all of the others dominate, which
+/// has `n` ones.
+#[inline]
+pub fn dominance_bottom(n: usize) -> Vec<usize> {
+    vec![1; n]
+}
+
+/// Finds the partitions that a partition covers in the dominance
+/// order, which are the ones just below it, with nothing in between.
+///
+/// The parts of `p` can be in any order, and the parts of each cover
+/// are in ascending order. A cover always comes from moving one cell
+/// down to a lower row of the Young diagram, and by Brylawski's
+/// theorem, it's a cover exactly when the rows are next to each other
+/// or the cell goes from a row of length `l + 2` to one of length `l`.
+pub fn covers(p: &[usize]) -> Vec<Vec<usize>> {
+    // The rows from the top, with an empty one below them for the cell
+    // to move into.
+    let mut rows: Vec<usize> = p.iter().cloned().filter(|&x| x > 0).collect();
+    rows.sort_by(|x, y| y.cmp(x));
+    rows.push(0);
+
+    let mut out = Vec::new();
+    for i in 0..rows.len() {
+        for j in (i + 1)..rows.len() {
+            // The cell has to come off the end of the last row of its
+            // length, and go onto the first row of the other length.
+            let last = i + 1 == rows.len() || rows[i + 1] < rows[i];
+            let first = rows[j - 1] > rows[j];
+            let close = j == i + 1 || rows[i] == rows[j] + 2;
+
+            if last && first && close && rows[i] >= rows[j] + 2 {
+                let mut q = rows.clone();
+                q[i] -= 1;
+                q[j] += 1;
+                q.retain(|&x| x > 0);
+                q.reverse();
+                out.push(q);
+            }
+        }
+    }
+
+    out
+}
+
+#[test]
+fn small() {
+    //! Checks a small case by hand, and that bad bases give nothing.
+
+    let all = collect!(Dominating::new(4, &[2, 2]));
+    assert_eq!(all, [&[4][..], &[3, 1], &[2, 2]]);
+
+    assert_eq!(Dominating::new(5, &[2, 2]).next(), None);
+
+    let mut p = Dominating::new(0, &[]);
+    assert_eq!(p.next(), Some(&[][..]));
+    assert_eq!(p.next(), None);
+}
+
+#[test]
+fn filtered() {
+    //! Compares against filtering every partition by dominance.
+
+    use Partition;
+
+    for n in 0..16 {
+        let all: Vec<Vec<usize>> = ::Partitions::new(n).into_iter().collect();
+
+        for base in &all {
+            let base_p = Partition::try_new(base.clone()).unwrap();
+            let mut expected: Vec<Vec<usize>> = all
+                .iter()
+                .filter(|x| Partition::try_new(x.to_vec()).unwrap() >= base_p)
+                .map(|x| x.iter().rev().cloned().collect())
+                .collect();
+            expected.sort_by(|x, y| y.cmp(x));
+
+            let actual = collect!(Dominating::new(n, base));
+
+            assert_eq!(actual, expected);
+        }
+    }
+}
+
+#[test]
+fn covers_walk() {
+    //! Checks that the covers are exactly the partitions just below,
+    //! and that walking down them from the top reaches everything.
+
+    use Partition;
+
+    assert_eq!(dominance_top(0), Vec::<usize>::new());
+    assert_eq!(dominance_bottom(0), Vec::<usize>::new());
+    assert_eq!(covers(&[]), Vec::<Vec<usize>>::new());
+    assert_eq!(covers(&[1, 1, 1]), Vec::<Vec<usize>>::new());
+    assert_eq!(covers(&[4]), [[1, 3]]);
+    assert_eq!(covers(&[3, 3]), [[1, 2, 3]]);
+    assert_eq!(covers(&[1, 5, 3]), [[1, 4, 4], [2, 2, 5]]);
+
+    for n in 0..10 {
+        let all: Vec<Partition> = ::Partitions::new(n)
+            .into_iter()
+            .map(|x| Partition::try_new(x).unwrap())
+            .collect();
+
+        let mut seen = vec![Partition::try_new(dominance_top(n)).unwrap()];
+        let mut i = 0;
+        while i < seen.len() {
+            let p = seen[i].clone();
+            for q in covers(p.parts()) {
+                let q = Partition::try_new(q).unwrap();
+                assert!(q < p);
+                assert!(!all.iter().any(|r| q < *r && *r < p));
+                if !seen.contains(&q) {
+                    seen.push(q);
+                }
+            }
+
+            i += 1;
+        }
+
+        assert_eq!(seen.len(), all.len());
+        assert!(seen.contains(&Partition::try_new(dominance_bottom(n)).unwrap()));
+        assert!(covers(&dominance_bottom(n)).is_empty());
+    }
+}
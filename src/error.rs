@@ -0,0 +1,25 @@
+//! The ways that making an iterator can fail.
+
+use core::fmt;
+
+/// The reason that an iterator couldn't be made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartitionsError {
+    /// The number can't be stored as the part type.
+    PartTooSmall(usize),
+    /// There isn't enough memory for the number's buffer, or its size
+    /// doesn't even fit in a `usize`.
+    TooBig(usize),
+}
+
+impl fmt::Display for PartitionsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PartitionsError::PartTooSmall(n) => write!(f, "{} doesn't fit in the part type", n),
+            PartitionsError::TooBig(n) => write!(f, "can't allocate a buffer for {}", n),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PartitionsError {}
@@ -0,0 +1,348 @@
+//! Partitions whose parts all come from a given set.
+
+use alloc::vec::Vec;
+
+/// Iterates over the partitions of a given integer whose parts all
+/// come from a given set.
+///
+/// The partitions come out in the same order as they would from
+/// `Partitions`, with the parts of each one in ascending order.
+pub struct FromSet {
+    /// The allowed parts, sorted and without duplicates.
+    set: Vec<usize>,
+    /// `reach[r]` is one more than the largest `i` such that `r` is a
+    /// sum of elements of `set[i..]`, which must be distinct when
+    /// `distinct` is set, or zero if there isn't one.
+    reach: Vec<usize>,
+    a: Vec<usize>,
+    i: Vec<usize>,
+    n: usize,
+    distinct: bool,
+    state: State,
+}
+
+enum State {
+    Start,
+    Running,
+    Done,
+}
+
+impl FromSet {
+    /// Makes a new iterator over the partitions of `n` into parts from
+    /// `parts`, which can be in any order and contain duplicates.
+    pub(crate) fn new(n: usize, parts: &[usize]) -> FromSet {
+        let mut set: Vec<usize> = parts.iter().cloned().filter(|&x| x > 0 && x <= n).collect();
+        set.sort();
+        set.dedup();
+
+        FromSet::sorted(n, set, false)
+    }
+
+    /// Makes a new iterator over the partitions of `n` into odd parts.
+    #[inline]
+    pub(crate) fn odd(n: usize) -> FromSet {
+        FromSet::sorted(n, (1..(n + 1)).step_by(2).collect(), false)
+    }
+
+    /// Makes a new iterator over the partitions of `n` into distinct
+    /// odd parts.
+    #[inline]
+    pub(crate) fn distinct_odd(n: usize) -> FromSet {
+        FromSet::sorted(n, (1..(n + 1)).step_by(2).collect(), true)
+    }
+
+    /// Makes a new iterator over the partitions of `n` into perfect
+    /// squares.
+    #[inline]
+    pub(crate) fn squares(n: usize) -> FromSet {
+        FromSet::sorted(n, (1..).map(|x| x * x).take_while(|&x| x <= n).collect(), false)
+    }
+
+    /// Makes a new iterator over the partitions of `n` into parts that
+    /// are `r` modulo `d`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `d` is zero.
+    #[inline]
+    pub(crate) fn modulo(n: usize, r: usize, d: usize) -> FromSet {
+        assert!(d != 0, "can't take parts modulo zero");
+        FromSet::sorted(n, (1..(n + 1)).filter(|&x| x % d == r).collect(), false)
+    }
+
+    /// Makes a new iterator over the partitions of `n` that have no
+    /// part equal to `f`.
+    #[inline]
+    pub(crate) fn forbidding(n: usize, f: usize) -> FromSet {
+        FromSet::sorted(n, (1..(n + 1)).filter(|&x| x != f).collect(), false)
+    }
+
+    /// Makes a new iterator over the partitions of `n` into primes.
+    pub(crate) fn primes(n: usize) -> FromSet {
+        // A sieve of Eratosthenes, which is plenty fast next to the
+        // enumeration itself.
+        let mut composite = vec![false; n + 1];
+        let mut primes = Vec::new();
+
+        for i in 2..(n + 1) {
+            if !composite[i] {
+                primes.push(i);
+                for j in (i * i..(n + 1)).step_by(i) {
+                    composite[j] = true;
+                }
+            }
+        }
+
+        FromSet::sorted(n, primes, false)
+    }
+
+    /// Makes a new iterator from a set of parts that's already sorted,
+    /// deduplicated, and between 1 and `n`.
+    fn sorted(n: usize, set: Vec<usize>, distinct: bool) -> FromSet {
+        let mut reach = vec![0; n + 1];
+        reach[0] = set.len() + 1;
+
+        for r in 1..(n + 1) {
+            for (i, &x) in set.iter().enumerate().rev() {
+                if x <= r && reach[r - x] > i + distinct as usize {
+                    reach[r] = i + 1;
+                    break;
+                }
+            }
+        }
+
+        FromSet {
+            set,
+            reach,
+            a: Vec::new(),
+            i: Vec::new(),
+            n,
+            distinct,
+            state: State::Start,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        match self.state {
+            State::Start => {
+                let n = self.n;
+                self.state = State::Running;
+
+                if self.reach[n] == 0 {
+                    self.state = State::Done;
+                    return None;
+                }
+
+                self.fill(n, 0);
+            },
+            State::Running => {
+                let mut r = match self.a.pop() {
+                    Some(x) => x,
+                    None => {
+                        self.state = State::Done;
+                        return None;
+                    },
+                };
+                self.i.pop();
+
+                loop {
+                    match self.a.pop() {
+                        Some(x) => {
+                            r += x;
+                            let j = self.i.pop().unwrap() + 1;
+
+                            if j < self.reach[r] {
+                                self.fill(r, j);
+                                break;
+                            }
+                        },
+                        None => {
+                            self.state = State::Done;
+                            return None;
+                        },
+                    }
+                }
+            },
+            State::Done => return None,
+        }
+
+        Some(&self.a)
+    }
+
+    /// Appends the smallest ascending run of parts from `set[j..]` that
+    /// sums to `r`, which must exist.
+    fn fill(&mut self, mut r: usize, mut j: usize) {
+        let d = self.distinct as usize;
+
+        while r > 0 {
+            while !(self.set[j] <= r && j + d < self.reach[r - self.set[j]]) {
+                j += 1;
+            }
+
+            self.a.push(self.set[j]);
+            self.i.push(j);
+            r -= self.set[j];
+            j += d;
+        }
+    }
+}
+
+#[test]
+fn coins() {
+    //! Checks some well known ways of making change.
+
+    assert_eq!(collect!(FromSet::new(100, &[1, 5, 10, 25])).len(), 242);
+    assert_eq!(collect!(FromSet::new(100, &[25, 1, 10, 5, 5, 1])).len(), 242);
+    assert_eq!(collect!(FromSet::new(200, &[1, 2, 5, 10, 20, 50, 100, 200])).len(), 73682);
+}
+
+#[test]
+fn filtered() {
+    //! Compares against filtering all of the partitions.
+
+    let sets: &[&[usize]] = &[
+        &[], &[0], &[1], &[2], &[3, 5], &[2, 3, 0, 2], &[4, 6, 9], &[1, 2, 3, 4, 5, 6],
+        &[7, 11, 13, 100],
+    ];
+
+    for set in sets {
+        for n in 0..25 {
+            let mut expected = Vec::new();
+            ::Partitions::new(n).for_each(|x| {
+                if x.iter().all(|y| set.contains(y)) {
+                    expected.push(x.to_vec());
+                }
+            });
+
+            assert_eq!(collect!(FromSet::new(n, set)), expected);
+        }
+    }
+}
+
+#[test]
+fn odd() {
+    //! Checks Euler's theorem, that there are as many partitions into
+    //! odd parts as there are into distinct parts.
+
+    for n in 0..41 {
+        let all = collect!(FromSet::odd(n));
+        assert!(all.iter().all(|x| x.iter().all(|y| y % 2 == 1)));
+
+        let mut p = ::Restricted::distinct(n);
+        let mut c = 0;
+        while p.next().is_some() {
+            c += 1;
+        }
+
+        assert_eq!(all.len(), c);
+    }
+}
+
+#[test]
+fn squares() {
+    //! Tests the first few entries of A001156.
+
+    let tests: &[usize] = &[
+        1, 1, 1, 1, 2, 2, 2, 2, 3, 4, 4, 4, 5, 6, 6, 6,
+        8, 9, 10, 10, 12, 13, 14, 14, 16, 19, 20, 21, 23, 26, 27,
+    ];
+
+    for (n, &c) in tests.iter().enumerate() {
+        assert_eq!(collect!(FromSet::squares(n)).len(), c);
+    }
+
+    assert_eq!(collect!(FromSet::squares(0)), vec![Vec::<usize>::new()]);
+    assert_eq!(collect!(FromSet::squares(2)), vec![vec![1, 1]]);
+}
+
+#[test]
+fn primes() {
+    //! Tests the first few entries of A000607.
+
+    let tests: &[usize] = &[
+        1, 0, 1, 1, 1, 2, 2, 3, 3, 4, 5, 6, 7, 9, 10, 12,
+        14, 17, 19, 23, 26, 30, 35, 40, 46, 52, 60, 67, 77, 87, 98,
+    ];
+
+    for (n, &c) in tests.iter().enumerate() {
+        assert_eq!(collect!(FromSet::primes(n)).len(), c);
+    }
+
+    assert!(collect!(FromSet::primes(1)).is_empty());
+    assert_eq!(collect!(FromSet::primes(0)), vec![Vec::<usize>::new()]);
+}
+
+#[test]
+fn distinct_odd() {
+    //! Compares against filtering the partitions into odd parts.
+
+    for n in 0..30 {
+        let mut expected = collect!(FromSet::odd(n));
+        expected.retain(|x| x.windows(2).all(|w| w[0] < w[1]));
+
+        assert_eq!(collect!(FromSet::distinct_odd(n)), expected);
+    }
+}
+
+#[test]
+fn forbidding() {
+    //! Compares against filtering every partition.
+
+    for n in 0..25 {
+        for f in 0..(n + 2) {
+            let mut expected = Vec::new();
+            ::Partitions::new(n).for_each(|x| {
+                if !x.contains(&f) {
+                    expected.push(x.to_vec());
+                }
+            });
+
+            let actual = collect!(FromSet::forbidding(n, f));
+            assert!(actual.iter().all(|x| !x.contains(&f)));
+            assert_eq!(actual, expected);
+        }
+    }
+}
+
+#[test]
+fn modulo() {
+    //! Compares against filtering, and checks the first Rogers-Ramanujan
+    //! identity, that the partitions into parts that are 1 or 4 modulo
+    //! 5 are as many as the ones whose parts differ by at least two.
+
+    for n in 0..25 {
+        for d in 1..6 {
+            for r in 0..(d + 1) {
+                let mut expected = Vec::new();
+                ::Partitions::new(n).for_each(|x| {
+                    if x.iter().all(|&y| y % d == r) {
+                        expected.push(x.to_vec());
+                    }
+                });
+
+                assert_eq!(collect!(FromSet::modulo(n, r, d)), expected);
+            }
+        }
+
+        let mut spread = 0;
+        ::Partitions::new(n).for_each(|x| {
+            if x.windows(2).all(|w| w[1] >= w[0] + 2) {
+                spread += 1;
+            }
+        });
+
+        let parts: Vec<usize> = (1..(n + 1)).filter(|x| x % 5 == 1 || x % 5 == 4).collect();
+        assert_eq!(collect!(FromSet::new(n, &parts)).len(), spread);
+    }
+}
+
+#[test]
+#[should_panic]
+fn modulo_zero() {
+    //! Checks that a modulus of zero is caught.
+
+    FromSet::modulo(5, 0, 0);
+}
@@ -0,0 +1,88 @@
+//! Glaisher's bijection between partitions into odd parts and
+//! partitions into distinct parts.
+//!
+//! Like the functions on shapes, these take and return partitions with
+//! their parts in ascending order.
+
+use alloc::vec::Vec;
+
+/// Maps a partition into odd parts to one into distinct parts.
+///
+/// Each odd part `x` that appears `m` times becomes the parts `x * 2^i`
+/// for each bit `2^i` in the binary expansion of `m`.
+pub fn odd_to_distinct(partition: &[usize]) -> Vec<usize> {
+    let mut out = Vec::new();
+
+    let mut i = 0;
+    while i < partition.len() {
+        let x = partition[i];
+        debug_assert!(x % 2 == 1, "{} isn't odd", x);
+
+        let mut m = 0;
+        while i < partition.len() && partition[i] == x {
+            m += 1;
+            i += 1;
+        }
+
+        let mut b = 1;
+        while m > 0 {
+            if m & 1 == 1 {
+                out.push(x * b);
+            }
+            m >>= 1;
+            b <<= 1;
+        }
+    }
+
+    out.sort();
+    out
+}
+
+/// Maps a partition into distinct parts to one into odd parts, undoing
+/// `odd_to_distinct`.
+///
+/// Each part is split into `2^i` copies of its odd part, where `2^i` is
+/// the largest power of two dividing it.
+pub fn distinct_to_odd(partition: &[usize]) -> Vec<usize> {
+    let mut out = Vec::new();
+
+    for &x in partition {
+        debug_assert!(x > 0, "parts must be positive");
+
+        let b = 1 << x.trailing_zeros();
+        for _ in 0..b {
+            out.push(x / b);
+        }
+    }
+
+    out.sort();
+    out
+}
+
+#[test]
+fn inverses() {
+    //! Checks that the maps are inverses, and land in the right sets.
+
+    for n in 0..31 {
+        let mut p = ::Partitions::odd_parts(n);
+        let mut c = 0;
+
+        while let Some(x) = p.next() {
+            let d = odd_to_distinct(x);
+            assert!(d.windows(2).all(|w| w[0] < w[1]));
+            assert_eq!(d.iter().sum::<usize>(), n);
+            assert_eq!(distinct_to_odd(&d), x);
+            c += 1;
+        }
+
+        let mut p = ::Partitions::distinct(n);
+        while let Some(x) = p.next() {
+            let o = distinct_to_odd(x);
+            assert!(o.iter().all(|y| y % 2 == 1));
+            assert_eq!(odd_to_distinct(&o), x);
+            c -= 1;
+        }
+
+        assert_eq!(c, 0);
+    }
+}
@@ -0,0 +1,382 @@
+//! Owned iteration over partitions.
+
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+use count::{count, MAX_COUNT};
+use {Part, Partitions};
+
+/// Iterates over partitions as owned vectors, from either end.
+///
+/// This is made by `Partitions::into_iter`, and unlike `Partitions`
+/// it's a proper `Iterator`, at the cost of allocating a vector for
+/// each partition.
+///
+/// Its `size_hint` is exact whenever the number of partitions left fits
+/// in a `usize`, but that stops being true at `n = 417`, so it isn't an
+/// `ExactSizeIterator`.
+pub struct IntoIter<T: Part = usize> {
+    front: Partitions<T>,
+    back: Option<Vec<usize>>,
+    remaining: u128,
+    /// Whether `remaining` is known at all, which it isn't if the
+    /// position isn't, so then the two ends stop when they meet.
+    known: bool,
+}
+
+impl<T: Part> IntoIterator for Partitions<T> {
+    type Item = Vec<T>;
+    type IntoIter = IntoIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> IntoIter<T> {
+        let n = self.a.len().saturating_sub(1);
+        let remaining = match self.yielded {
+            Some(yielded) if n <= MAX_COUNT => count(n).saturating_sub(yielded as u128),
+            _ => u128::MAX,
+        };
+
+        IntoIter {
+            remaining,
+            known: self.yielded.is_some(),
+            front: self,
+            back: None,
+        }
+    }
+}
+
+impl<T: Part> Iterator for IntoIter<T> {
+    type Item = Vec<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+        let x = self.front.next()?.to_vec();
+
+        // The back has already had this one, and everything after it.
+        if !self.known && self.back.as_ref().is_some_and(|a| same(&x, a)) {
+            self.remaining = 0;
+            return None;
+        }
+
+        Some(x)
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Vec<T>> {
+        // Skipping through the lending iterator avoids allocating for
+        // the partitions in between.
+        let skip = (n as u128).min(self.remaining) as usize;
+        self.front.advance_by_partitions(skip);
+
+        self.remaining -= skip as u128;
+        self.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if !self.known {
+            (0, None)
+        } else if self.remaining > usize::MAX as u128 {
+            (usize::MAX, None)
+        } else {
+            (self.remaining as usize, Some(self.remaining as usize))
+        }
+    }
+}
+
+impl<T: Part> DoubleEndedIterator for IntoIter<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Vec<T>> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+
+        let a = match self.back {
+            Some(ref mut a) => {
+                let more = prev(a);
+                debug_assert!(more || !self.known);
+                if !more {
+                    self.remaining = 0;
+                    return None;
+                }
+                a
+            },
+            None => {
+                let n = self.front.a.len().saturating_sub(1);
+                self.back.get_or_insert(if n == 0 { vec![] } else { vec![n] })
+            },
+        };
+
+        // Without a count, the back has to stop at the partition that
+        // the front is on, which it has already had.
+        if !self.known && same(self.front.current(), a) {
+            self.remaining = 0;
+            return None;
+        }
+
+        Some(a.iter().map(|&x| T::from_usize(x)).collect())
+    }
+}
+
+impl<T: Part> FusedIterator for IntoIter<T> {}
+
+fn same<T: Part>(x: &[T], y: &[usize]) -> bool {
+    x.len() == y.len() && x.iter().zip(y).all(|(a, &b)| a.to_usize() == b)
+}
+
+/// Finds the partition that comes after `parts` in the order that
+/// `Partitions` yields them, or `None` if it's the last one.
+///
+/// The parts must be positive and in ascending order.
+pub fn next_partition(parts: &[usize]) -> Option<Vec<usize>> {
+    debug_assert!(parts.windows(2).all(|w| w[0] <= w[1]), "the parts must be ascending");
+    debug_assert!(parts.first() != Some(&0), "the parts must be positive");
+
+    let n = parts.iter().sum();
+    Partitions::after(n, parts, None).next().map(|x| x.to_vec())
+}
+
+/// Finds the partition that comes before `parts` in the order that
+/// `Partitions` yields them, or `None` if it's the first one.
+///
+/// The parts must be positive and in ascending order.
+pub fn prev_partition(parts: &[usize]) -> Option<Vec<usize>> {
+    debug_assert!(parts.windows(2).all(|w| w[0] <= w[1]), "the parts must be ascending");
+    debug_assert!(parts.first() != Some(&0), "the parts must be positive");
+
+    let mut a = parts.to_vec();
+    if prev(&mut a) {
+        Some(a)
+    } else {
+        None
+    }
+}
+
+/// Replaces an ascending partition with the one before it, returning
+/// false if it was the first.
+fn prev(a: &mut Vec<usize>) -> bool {
+    let len = a.len();
+    if len == 0 {
+        return false;
+    }
+
+    // Splitting the last part in half is the smallest step back, if
+    // the halves would still be in order.
+    let last = a[len - 1];
+    let low = if len >= 2 { a[len - 2] } else { 1 };
+    if last / 2 >= low {
+        a[len - 1] = last / 2;
+        a.push(last - last / 2);
+        return true;
+    }
+
+    // Otherwise, the last part that can shrink does, and everything
+    // after it is merged into one big part.
+    let mut rest = last;
+    for i in (0..(len - 1)).rev() {
+        rest += a[i];
+
+        let low = if i >= 1 { a[i - 1] } else { 1 };
+        if a[i] > low {
+            let x = a[i] - 1;
+            a.truncate(i);
+            a.push(x);
+            a.push(rest - x);
+            return true;
+        }
+    }
+
+    false
+}
+
+#[test]
+fn stateless() {
+    //! Checks that stepping from either end goes through the same
+    //! partitions as the iterator.
+
+    for n in 0..20 {
+        let all: Vec<Vec<usize>> = Partitions::new(n).into_iter().collect();
+
+        let mut forward = vec![all[0].clone()];
+        while let Some(x) = next_partition(forward.last().unwrap()) {
+            forward.push(x);
+        }
+        assert_eq!(forward, all);
+
+        let mut backward = vec![all[all.len() - 1].clone()];
+        while let Some(x) = prev_partition(backward.last().unwrap()) {
+            backward.push(x);
+        }
+        backward.reverse();
+        assert_eq!(backward, all);
+    }
+
+    assert_eq!(next_partition(&[]), None);
+    assert_eq!(prev_partition(&[]), None);
+    assert_eq!(next_partition(&[1, 1, 2]), Some(vec![1, 3]));
+    assert_eq!(prev_partition(&[1, 3]), Some(vec![1, 1, 2]));
+}
+
+#[test]
+fn rev() {
+    //! Checks that iterating backwards gives the reversed sequence.
+
+    for n in 0..25 {
+        let forward: Vec<_> = Partitions::new(n).into_iter().collect();
+        let mut backward: Vec<_> = Partitions::new(n).into_iter().rev().collect();
+        backward.reverse();
+
+        assert_eq!(forward, backward);
+    }
+}
+
+#[test]
+fn meet() {
+    //! Checks that alternating between the ends yields everything once.
+
+    for n in 0..25 {
+        let expected: Vec<_> = Partitions::new(n).into_iter().collect();
+
+        let mut iter = Partitions::new(n).into_iter();
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+
+        while let Some(x) = iter.next() {
+            front.push(x);
+            match iter.next_back() {
+                Some(x) => back.push(x),
+                None => break,
+            }
+        }
+
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        back.reverse();
+        front.extend(back);
+        assert_eq!(front, expected);
+    }
+}
+
+#[test]
+fn len() {
+    //! Checks that the length counts down from both ends, and that it's
+    //! left open when it doesn't fit in a `usize`.
+
+    let mut iter = Partitions::new(20).into_iter();
+    assert_eq!(iter.size_hint(), (627, Some(627)));
+
+    for i in 0..300 {
+        assert!(iter.next().is_some());
+        assert!(iter.next_back().is_some());
+        let left = 627 - 2 * (i + 1);
+        assert_eq!(iter.size_hint(), (left, Some(left)));
+    }
+
+    assert_eq!(iter.by_ref().count(), 27);
+    assert_eq!(iter.size_hint(), (0, Some(0)));
+
+    assert_eq!(Partitions::new(500).into_iter().size_hint(), (usize::MAX, None));
+}
+
+#[test]
+fn nth() {
+    //! Checks that skipping ahead agrees with collecting.
+
+    for n in 0..15 {
+        let all: Vec<_> = Partitions::new(n).into_iter().collect();
+
+        for i in 0..(all.len() + 2) {
+            assert_eq!(Partitions::new(n).into_iter().nth(i).as_ref(), all.get(i));
+        }
+
+        let mut iter = Partitions::new(n).into_iter();
+        assert_eq!(iter.nth(1).as_ref(), all.get(1));
+        assert_eq!(iter.nth(2).as_ref(), all.get(4));
+        let left = all.len().saturating_sub(5);
+        assert_eq!(iter.size_hint(), (left, Some(left)));
+    }
+}
+
+#[test]
+fn fused() {
+    //! Checks that the iterator stays finished from both ends.
+
+    for n in 0..6 {
+        let mut iter = Partitions::new(n).into_iter();
+        while iter.next().is_some() {}
+
+        for _ in 0..3 {
+            assert_eq!(iter.next(), None);
+            assert_eq!(iter.next_back(), None);
+        }
+    }
+}
+
+#[test]
+fn partly_used() {
+    //! Checks that the ends still meet after the partitions have been
+    //! partly consumed.
+
+    let expected: Vec<_> = Partitions::new(12).into_iter().skip(10).collect();
+
+    let mut p = Partitions::new(12);
+    for _ in 0..10 {
+        p.next();
+    }
+
+    let mut actual: Vec<_> = p.into_iter().rev().collect();
+    actual.reverse();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn meet_unknown() {
+    //! Checks that the ends still meet when the position isn't known,
+    //! after resuming near the end of a number too big to rank.
+
+    let n = ::MAX_RANK + 84;
+    let mut iter = Partitions::resume_after(n, &[n]).unwrap().into_iter();
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    let mut last = vec![vec![n]];
+    for _ in 0..4 {
+        let x = prev_partition(last.last().unwrap()).unwrap();
+        last.push(x);
+    }
+
+    // Resuming after the fifth from last leaves four.
+    let p = Partitions::resume_after(n, &last[4]).unwrap();
+    let back: Vec<_> = p.into_iter().rev().take(10).collect();
+    assert_eq!(back, &last[..4]);
+
+    for i in 0..4 {
+        let mut iter = Partitions::resume_after(n, &last[4]).unwrap().into_iter();
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        for _ in 0..i {
+            front.extend(iter.next());
+        }
+        while let Some(x) = iter.next_back() {
+            back.push(x);
+            front.extend(iter.next());
+        }
+
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        back.reverse();
+        front.extend(back);
+        let mut expected = last[..4].to_vec();
+        expected.reverse();
+        assert_eq!(front, expected);
+    }
+}
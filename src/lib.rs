@@ -3,94 +3,523 @@
 //! This is an implementation of a method described by
 //! [Jerome Kelleher](http://jeromekelleher.net/generating-integer-partitions.html),
 //! which takes a constant amount of time for each partition.
+//!
+//! The crate only needs `alloc`, so it can be used without `std` by
+//! turning off the default features.
 
 #![deny(missing_docs)]
+#![no_std]
+
+#[macro_use]
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
+#[cfg(feature = "stream")]
+extern crate futures_core;
+#[cfg(all(feature = "stream", test))]
+extern crate futures;
+#[cfg(feature = "bignum")]
+extern crate num_bigint;
+#[cfg(feature = "rand")]
+extern crate rand;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(feature = "serde", test))]
+extern crate serde_json;
+#[cfg(feature = "smallvec")]
+extern crate smallvec;
+
+// Drains one of the lending iterators into owned partitions, for tests
+// to compare. It's defined before the modules so that they can all use
+// it.
+#[cfg(test)]
+macro_rules! collect {
+    ($p:expr) => {{
+        let p = &mut $p;
+        let mut all = ::alloc::vec::Vec::new();
+        while let Some(x) = p.next() {
+            all.push(x.to_vec());
+        }
+        all
+    }};
+}
+
+mod adapters;
+mod arr;
+mod compositions;
+mod count;
+mod dominance;
+mod error;
+mod from_set;
+mod glaisher;
+mod iter;
+mod multiset;
+mod orders;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod part;
+mod partition;
+mod plane;
+mod pool;
+mod rank;
+mod restricted;
+#[cfg(feature = "rand")]
+mod sample;
+mod shape;
+mod sizes;
+#[cfg(feature = "smallvec")]
+mod small;
+#[cfg(feature = "stream")]
+mod stream;
+mod subpartitions;
+mod top;
+#[cfg(feature = "std")]
+mod write;
+
+pub use adapters::{
+    AllUpTo, BoxedSlices, Checked, Descending, Enumerated, MapParts, Multiplicities, Padded, Parity,
+    SelfConjugate, Shared, Tagged, WithConjugate,
+};
+#[cfg(target_has_atomic = "64")]
+pub use adapters::Progress;
+pub use arr::PartitionsArr;
+pub use compositions::Compositions;
+pub use count::{
+    count, count_at_most_parts, count_distinct, count_distinct_k, count_k, count_max_part,
+    count_mod, largest_part_distribution, length_histogram, restricted_count, PartitionTable,
+    MAX_COUNT,
+};
+#[cfg(feature = "bignum")]
+pub use count::count_big;
+pub use dominance::{covers, dominance_bottom, dominance_top, Dominating};
+pub use error::PartitionsError;
+pub use from_set::FromSet;
+pub use glaisher::{distinct_to_odd, odd_to_distinct};
+pub use iter::{next_partition, prev_partition, IntoIter};
+pub use multiset::MultisetPartitions;
+pub use orders::{Gray, Lexicographic, Move};
+#[cfg(feature = "rayon")]
+pub use parallel::par_partitions;
+pub use part::Part;
+pub use partition::{is_partition_of, partition_sum, partition_union, Partition, Typed};
+pub use plane::PlanePartitions;
+pub use pool::{PartitionPool, Pooled};
+pub use rank::{rank, split_ranges, unrank, Cursor, MAX_RANK};
+pub use restricted::{grouped_by_parts, Builder, LargestExactly, Restricted};
+#[cfg(feature = "rand")]
+pub use sample::{sample, MAX_SAMPLE};
+pub use shape::{
+    conjugate, conjugate_into, durfee_square, dyson_rank, ferrers, ferrers_into, ferrers_with,
+    height, hook_lengths, is_staircase, num_sytx, weight, width,
+};
+pub use sizes::{DistinctSizes, LimitedKinds};
+#[cfg(feature = "smallvec")]
+pub use small::PartitionsSmall;
+#[cfg(feature = "stream")]
+pub use stream::{partition_stream, PartitionStream};
+pub use subpartitions::SubPartitions;
+pub use top::top_k_by;
+#[cfg(feature = "std")]
+pub use write::write_all;
+
+#[cfg(target_has_atomic = "64")]
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::AtomicU64;
 
 /// Iterates over the partitions of a given positive integer.
-pub struct Partitions {
-    a: Vec<usize>,
+///
+/// The partitions can also be iterated over as owned vectors, from
+/// either end, by using `into_iter`.
+///
+/// The parts are `usize`s by default, but they can be stored as any
+/// other `Part` type by making the iterator with `of`.
+///
+/// Cloning the iterator takes a snapshot of it, which can be advanced
+/// separately from the original.
+///
+/// With the `serde` feature, the iterator can be serialized at any
+/// point, and deserializing it will carry on from the same place.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Partitions<T: Part = usize> {
+    a: Vec<T>,
     k: usize,
     y: usize,
     next: State,
+    /// How many partitions have come out, or `None` if that isn't
+    /// known, which only happens after `resume_after` with a number
+    /// too big to rank. This is a `u64` like a `Cursor`, since it can
+    /// go past `usize::MAX` on 32-bit targets.
+    yielded: Option<u64>,
 }
 
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum State {
+    /// The empty partition of zero is still to come.
+    EmptyOnce,
     A,
     B { x: usize, l: usize },
 }
 
+/// Shows where the iterator is up to, without the whole buffer.
+impl<T: Part> fmt::Debug for Partitions<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Partitions")
+            .field("n", &(self.a.len() - 1))
+            .field("parts", &self.num_parts())
+            .field("yielded", &self.yielded)
+            .field("k", &self.k)
+            .field("y", &self.y)
+            .field("next", &self.next)
+            .finish()
+    }
+}
+
 impl Partitions {
     /// Makes a new iterator.
     #[inline]
     pub fn new(n: usize) -> Partitions {
-        Partitions {
-            a: vec![0; n + 1],
-            k: if n == 0 { 0 } else { 1 },
-            y: if n == 0 { 0 } else { n - 1 },
-            next: State::A,
-        }
+        Partitions::of(n)
     }
 
-    /// Advances the iterator and returns the next partition.
+    /// Makes a new iterator, or returns an error if there isn't enough
+    /// memory for it.
     #[inline]
-    pub fn next(&mut self) -> Option<&[usize]> {
-        let Partitions {
-            ref mut a,
-            ref mut k,
-            ref mut y,
-            ref mut next
-        } = *self;
-
-        match *next {
-            State::A => {
-                if *k == 0 {
-                    if a.len() == 1 {
-                        a.pop();
-                        Some(&[])
-                    } else {
-                        None
-                    }
-                } else {
-                    *k -= 1;
-                    let x = a[*k] + 1;
-
-                    while 2 * x <= *y {
-                        a[*k] = x;
-                        *y -= x;
-                        *k += 1;
-                    }
-
-                    let l = *k + 1;
-
-                    if x <= *y {
-                        a[*k] = x;
-                        a[l] = *y;
-                        *next = State::B { x, l };
-                        Some(&a[..*k + 2])
-                    } else {
-                        a[*k] = x + *y;
-                        *y = x + *y - 1;
-                        Some(&a[..*k + 1])
-                    }
-                }
-            },
-            State::B { mut x, l } => {
-                x += 1;
-                *y -= 1;
+    pub fn try_new(n: usize) -> Result<Partitions, PartitionsError> {
+        Partitions::try_of(n)
+    }
 
-                if x <= *y {
-                    a[*k] = x;
-                    a[l] = *y;
-                    *next = State::B { x, l };
-                    Some(&a[..*k + 2])
-                } else {
-                    a[*k] = x + *y;
-                    *y = x + *y - 1;
-                    *next = State::A;
-                    Some(&a[..*k + 1])
-                }
-            },
+    /// Makes a builder for an iterator over the partitions of `n` with
+    /// any combination of restrictions.
+    #[inline]
+    pub fn builder(n: usize) -> Builder {
+        Builder::new(n)
+    }
+
+    /// Makes an iterator over the partitions of every integer from zero
+    /// to `max`, reusing one buffer for all of them.
+    #[inline]
+    pub fn all_up_to(max: usize) -> AllUpTo {
+        AllUpTo::new(max)
+    }
+
+    /// Makes an iterator over the partitions with no part greater
+    /// than `m`.
+    #[inline]
+    pub fn with_max_part(n: usize, m: usize) -> Restricted {
+        Restricted::with_max_part(n, m)
+    }
+
+    /// Makes an iterator over the partitions whose largest part is
+    /// exactly `m`.
+    #[inline]
+    pub fn largest_exactly(n: usize, m: usize) -> LargestExactly {
+        LargestExactly::new(n, m)
+    }
+
+    /// Makes an iterator over the partitions with no part less than
+    /// `m`.
+    #[inline]
+    pub fn min_part(n: usize, m: usize) -> Restricted {
+        Restricted::min_part(n, m)
+    }
+
+    /// Makes an iterator over the partitions with exactly `k` parts.
+    #[inline]
+    pub fn into_parts(n: usize, k: usize) -> Restricted {
+        Restricted::into_parts(n, k)
+    }
+
+    /// Makes an iterator over the partitions with at most `k` parts.
+    ///
+    /// By conjugation, there are as many of these as there are
+    /// partitions with no part greater than `k`.
+    #[inline]
+    pub fn at_most_parts(n: usize, k: usize) -> Restricted {
+        Restricted::at_most_parts(n, k)
+    }
+
+    /// Makes an iterator over the partitions with at most `r` parts,
+    /// none of which is greater than `c`, which are the ones that fit
+    /// in an `r` by `c` box.
+    #[inline]
+    pub fn boxed(n: usize, r: usize, c: usize) -> Restricted {
+        Restricted::boxed(n, r, c)
+    }
+
+    /// Makes an iterator over the partitions into distinct parts.
+    #[inline]
+    pub fn distinct(n: usize) -> Restricted {
+        Restricted::distinct(n)
+    }
+
+    /// Makes an iterator over the partitions into exactly `k` distinct
+    /// parts.
+    #[inline]
+    pub fn distinct_into_parts(n: usize, k: usize) -> Restricted {
+        Restricted::distinct_into_parts(n, k)
+    }
+
+    /// Makes an iterator over the partitions into distinct parts, none
+    /// of which is less than `m`.
+    #[inline]
+    pub fn distinct_min(n: usize, m: usize) -> Restricted {
+        Restricted::distinct_min(n, m)
+    }
+
+    /// Makes an iterator over the partitions with at most `d` different
+    /// sizes of part.
+    #[inline]
+    pub fn distinct_sizes_at_most(n: usize, d: usize) -> DistinctSizes {
+        DistinctSizes::new(n, d)
+    }
+
+    /// Makes an iterator over the partitions with at most `d` different
+    /// sizes of part, as `(part, multiplicity)` pairs.
+    #[inline]
+    pub fn limited_kinds(n: usize, d: usize) -> LimitedKinds {
+        LimitedKinds::new(n, d)
+    }
+
+    /// Makes an iterator over the partitions that dominate `base`, which
+    /// can have its parts in any order, with their parts in descending
+    /// order.
+    ///
+    /// There are none if `base` isn't a partition of `n`.
+    #[inline]
+    pub fn dominating(n: usize, base: &[usize]) -> Dominating {
+        Dominating::new(n, base)
+    }
+
+    /// Makes an iterator over the partitions whose parts all come from
+    /// `parts`, which can be in any order and contain duplicates.
+    #[inline]
+    pub fn from_parts(n: usize, parts: &[usize]) -> FromSet {
+        FromSet::new(n, parts)
+    }
+
+    /// Makes an iterator over the partitions with no part equal to `f`.
+    #[inline]
+    pub fn forbidding(n: usize, f: usize) -> FromSet {
+        FromSet::forbidding(n, f)
+    }
+
+    /// Makes an iterator over the partitions into odd parts.
+    #[inline]
+    pub fn odd_parts(n: usize) -> FromSet {
+        FromSet::odd(n)
+    }
+
+    /// Makes an iterator over the partitions into parts that are `r`
+    /// modulo `d`, so a remainder of zero gives the multiples of `d`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `d` is zero.
+    #[inline]
+    pub fn parts_mod(n: usize, r: usize, d: usize) -> FromSet {
+        FromSet::modulo(n, r, d)
+    }
+
+    /// Makes an iterator over the partitions into perfect squares.
+    #[inline]
+    pub fn square_parts(n: usize) -> FromSet {
+        FromSet::squares(n)
+    }
+
+    /// Makes an iterator over the partitions into primes.
+    #[inline]
+    pub fn prime_parts(n: usize) -> FromSet {
+        FromSet::primes(n)
+    }
+
+    /// Makes an iterator over the partitions that are their own
+    /// conjugates.
+    #[inline]
+    pub fn self_conjugate(n: usize) -> SelfConjugate {
+        SelfConjugate::new(n)
+    }
+
+    /// Makes an iterator over the partitions in reverse lexicographic
+    /// order, with their parts in descending order.
+    #[inline]
+    pub fn lexicographic(n: usize) -> Lexicographic {
+        Lexicographic::new(n)
+    }
+
+    /// Makes an iterator over the partitions in a Gray code order, with
+    /// their parts in descending order.
+    #[inline]
+    pub fn gray(n: usize) -> Gray {
+        Gray::new(n)
+    }
+
+    /// Makes an iterator that checks each partition in debug builds,
+    /// to catch bugs in the algorithm.
+    #[inline]
+    pub fn checked(n: usize) -> Checked {
+        Checked::new(n)
+    }
+
+    /// Makes an iterator over the partitions in colexicographic order,
+    /// with their parts in descending order.
+    ///
+    /// One list of parts is colexicographically less than another if
+    /// it's lexicographically less once both are reversed. Reversing
+    /// descending parts gives ascending ones, so this is just the usual
+    /// order, with the parts the other way around.
+    #[inline]
+    pub fn colex(n: usize) -> Descending {
+        Descending::new(n)
+    }
+
+    /// Makes an iterator that adds one to `counter` for each partition
+    /// it yields, so that other threads can see how far it's got.
+    #[cfg(target_has_atomic = "64")]
+    #[inline]
+    pub fn with_progress(n: usize, counter: Arc<AtomicU64>) -> Progress {
+        Progress::new(n, counter)
+    }
+
+    /// Makes an iterator over the partitions with an even number of
+    /// parts.
+    #[inline]
+    pub fn even_length(n: usize) -> Parity {
+        Parity::new(n, 0)
+    }
+
+    /// Makes an iterator over the partitions with an odd number of
+    /// parts.
+    #[inline]
+    pub fn odd_length(n: usize) -> Parity {
+        Parity::new(n, 1)
+    }
+
+    /// Makes an iterator that yields the parts in descending order,
+    /// rather than ascending.
+    #[inline]
+    pub fn descending(n: usize) -> Descending {
+        Descending::new(n)
+    }
+
+    /// Makes an iterator that yields the parts in descending order,
+    /// followed by enough zeros to make `n` of them.
+    #[inline]
+    pub fn padded(n: usize) -> Padded {
+        Padded::new(n)
+    }
+
+    /// Makes an iterator that yields each partition as a list of
+    /// `(part, multiplicity)` pairs.
+    #[inline]
+    pub fn multiplicities(n: usize) -> Multiplicities {
+        Multiplicities::new(n)
+    }
+
+    /// Makes an iterator that yields each partition as a boxed slice,
+    /// which takes up less memory than a vector when kept around.
+    #[inline]
+    pub fn boxed_slices(n: usize) -> BoxedSlices {
+        BoxedSlices::new(n)
+    }
+
+    /// Makes an iterator that yields each partition along with its
+    /// conjugate.
+    #[inline]
+    pub fn with_conjugate(n: usize) -> WithConjugate {
+        WithConjugate::new(n)
+    }
+
+    /// Makes an iterator that yields each partition as an `Rc<[usize]>`,
+    /// reusing the ones that have been dropped.
+    #[inline]
+    pub fn shared(n: usize) -> Shared {
+        Shared::new(n)
+    }
+
+    /// Makes an iterator that yields each partition along with `n`.
+    #[inline]
+    pub fn tagged(n: usize) -> Tagged {
+        Tagged::new(n)
+    }
+
+    /// Turns the iterator into one over owned `Partition`s.
+    #[inline]
+    pub fn typed(self) -> Typed {
+        Typed::new(self.into_iter())
+    }
+
+    /// Makes an iterator that yields each partition as a `SmallVec`,
+    /// which only allocates if it has more than 16 parts.
+    #[cfg(feature = "smallvec")]
+    #[inline]
+    pub fn small(n: usize) -> PartitionsSmall {
+        PartitionsSmall::new(n)
+    }
+
+    /// Makes an iterator that carries on from a cursor, as returned by
+    /// `cursor`, on the partitions of `n`.
+    ///
+    /// A cursor past the last partition gives an iterator that's
+    /// already finished.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n > MAX_RANK`.
+    pub fn from_cursor(n: usize, cursor: Cursor) -> Partitions {
+        // A cursor past the end is the same as one right at the end.
+        let index = (cursor.index() as u128).min(count(n)) as u64;
+        if index == 0 {
+            return Partitions::new(n);
+        }
+
+        let parts = match unrank(n, index - 1) {
+            Some(parts) => parts,
+            None if n == 0 => Vec::new(),
+            None => vec![n],
+        };
+
+        Partitions::after(n, &parts, Some(index))
+    }
+
+    /// Makes an iterator over the partitions of `n` from position
+    /// `start` up to, but not including, position `end`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n > MAX_RANK`.
+    pub fn range(n: usize, start: u64, end: u64) -> PartitionRange {
+        PartitionRange {
+            inner: Partitions::from_cursor(n, Cursor::at(start)),
+            left: end.saturating_sub(start),
+        }
+    }
+
+    /// Makes an iterator that carries on after the partition `p` of
+    /// `n`, or returns `None` if `p` isn't one, with positive parts in
+    /// ascending order.
+    ///
+    /// The position is only known for `n <= MAX_RANK`, so otherwise
+    /// `cursor` returns `None` and `into_iter` has no exact length.
+    pub fn resume_after(n: usize, p: &[usize]) -> Option<Partitions> {
+        if !is_partition_of(n, p) {
+            return None;
         }
+
+        let yielded = if n <= MAX_RANK {
+            Some(rank(p) + 1)
+        } else {
+            None
+        };
+
+        Some(Partitions::after(n, p, yielded))
     }
 
     /// Makes a new iterator, trying to avoid allocations.
@@ -102,30 +531,347 @@ impl Partitions {
     #[inline]
     pub fn recycle(n: usize, mut vec: Vec<usize>) -> Partitions {
         vec.clear();
-        vec.reserve(n + 1);
-        for _ in 0..(n + 1) {
-            vec.push(0);
-        }
+        vec.resize(n + 1, 0);
 
         Partitions {
             a: vec,
             k: if n == 0 { 0 } else { 1 },
             y: if n == 0 { 0 } else { n - 1 },
+            next: if n == 0 { State::EmptyOnce } else { State::A },
+            yielded: Some(0),
+        }
+    }
+
+    /// Makes an iterator that carries on after `parts`, which must be a
+    /// partition of `n` in ascending order, as if `yielded` partitions
+    /// had come before it.
+    fn after(n: usize, parts: &[usize], yielded: Option<u64>) -> Partitions {
+        let mut a = vec![0; n + 1];
+        a[..parts.len()].copy_from_slice(parts);
+
+        // This is the state after a partition comes out of case A,
+        // which can be followed from anywhere.
+        Partitions {
+            a,
+            k: parts.len().saturating_sub(1),
+            y: parts.last().map_or(0, |&x| x - 1),
             next: State::A,
+            yielded,
+        }
+    }
+}
+
+/// Iterates over the partitions in a range of positions.
+///
+/// This is made by `Partitions::range`.
+pub struct PartitionRange {
+    inner: Partitions,
+    left: u64,
+}
+
+impl PartitionRange {
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        if self.left == 0 {
+            return None;
+        }
+
+        self.left -= 1;
+        self.inner.next()
+    }
+}
+
+impl<T: Part> Partitions<T> {
+    /// Makes a new iterator whose parts are of type `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is too big to be stored as a `T`.
+    #[inline]
+    pub fn of(n: usize) -> Partitions<T> {
+        Partitions::try_of(n).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Makes a new iterator whose parts are of type `T`, or returns an
+    /// error if `n` is too big to be stored as a `T`, or if there isn't
+    /// enough memory for it.
+    pub fn try_of(n: usize) -> Result<Partitions<T>, PartitionsError> {
+        if T::from_usize(n).to_usize() != n {
+            return Err(PartitionsError::PartTooSmall(n));
+        }
+
+        // Asking for the memory up front means that failing is an error
+        // rather than an abort.
+        let len = n.checked_add(1).ok_or(PartitionsError::TooBig(n))?;
+        let mut a = Vec::new();
+        a.try_reserve_exact(len).map_err(|_| PartitionsError::TooBig(n))?;
+        a.resize(len, T::from_usize(0));
+
+        Ok(Partitions {
+            a,
+            k: if n == 0 { 0 } else { 1 },
+            y: if n == 0 { 0 } else { n - 1 },
+            next: if n == 0 { State::EmptyOnce } else { State::A },
+            yielded: Some(0),
+        })
+    }
+
+    /// Advances the iterator and returns the next partition.
+    ///
+    /// The returned slice borrows the iterator's internal buffer, so
+    /// no allocations happen during iteration. This is also why
+    /// `Partitions` can't implement `Iterator`.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[T]> {
+        let len = step(&mut self.a, &mut self.k, &mut self.y, &mut self.next)?;
+        if let Some(ref mut yielded) = self.yielded {
+            *yielded += 1;
+        }
+        Some(&self.a[..len])
+    }
+
+    /// Starts the iteration over again from the first partition, reusing
+    /// the same buffer.
+    #[inline]
+    pub fn reset(&mut self) {
+        let n = self.a.len() - 1;
+
+        for x in &mut self.a {
+            *x = T::from_usize(0);
+        }
+
+        self.k = if n == 0 { 0 } else { 1 };
+        self.y = if n == 0 { 0 } else { n - 1 };
+        self.next = if n == 0 { State::EmptyOnce } else { State::A };
+        self.yielded = Some(0);
+    }
+
+    /// Returns a cursor for the current position, which can be passed
+    /// to `Partitions::from_cursor` to carry on from here, or `None` if
+    /// the position isn't known.
+    #[inline]
+    pub fn cursor(&self) -> Option<Cursor> {
+        self.yielded.map(Cursor::at)
+    }
+
+    /// Skips over the next `count` partitions without returning them,
+    /// and returns how many there actually were.
+    #[inline]
+    pub fn advance_by_partitions(&mut self, count: usize) -> usize {
+        let mut skipped = 0;
+        while skipped < count && self.next().is_some() {
+            skipped += 1;
+        }
+        skipped
+    }
+
+    /// Returns the number of parts in the partition that was yielded
+    /// most recently, or zero if there hasn't been one yet.
+    #[inline]
+    pub fn num_parts(&self) -> usize {
+        if self.yielded == Some(0) || self.a.len() == 1 {
+            0
+        } else if let State::B { .. } = self.next {
+            self.k + 2
+        } else {
+            self.k + 1
+        }
+    }
+
+    /// Returns the partition that was yielded most recently, or nothing
+    /// if there hasn't been one yet.
+    #[inline]
+    pub(crate) fn current(&self) -> &[T] {
+        &self.a[..self.num_parts()]
+    }
+
+    /// Returns the largest part of the partition that was yielded most
+    /// recently, or `None` if there hasn't been one or it was empty.
+    #[inline]
+    pub fn largest_part(&self) -> Option<usize> {
+        match self.num_parts() {
+            0 => None,
+            len => Some(self.a[len - 1].to_usize()),
         }
     }
 
+    /// Returns the smallest part of the partition that was yielded
+    /// most recently, or `None` if there hasn't been one or it was
+    /// empty.
+    #[inline]
+    pub fn smallest_part(&self) -> Option<usize> {
+        match self.num_parts() {
+            0 => None,
+            _ => Some(self.a[0].to_usize()),
+        }
+    }
+
+    /// Returns how many times `value` is a part of the partition that
+    /// was yielded most recently.
+    ///
+    /// The parts are sorted, so this is a pair of binary searches
+    /// rather than a scan.
+    #[inline]
+    pub fn count_of_part(&self, value: usize) -> usize {
+        let x = self.current();
+        let lo = x.partition_point(|y| y.to_usize() < value);
+        let hi = x.partition_point(|y| y.to_usize() <= value);
+        hi - lo
+    }
+
+    /// Turns the iterator into one that applies `f` to each part of
+    /// each partition.
+    #[inline]
+    pub fn map_parts<F: FnMut(usize) -> usize>(self, f: F) -> MapParts<T, F> {
+        MapParts::new(self, f)
+    }
+
+    /// Turns the iterator into one over owned vectors, each with its
+    /// index in the whole sequence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position isn't known, like after `resume_after`
+    /// with `n > MAX_RANK`.
+    #[inline]
+    pub fn enumerated(self) -> Enumerated<T> {
+        Enumerated::new(self)
+    }
+
+    /// Calls a closure on each remaining partition.
+    ///
+    /// This is a convenience for the usual `while let` loop, and
+    /// likewise never allocates.
+    #[inline]
+    pub fn for_each<F: FnMut(&[T])>(mut self, mut f: F) {
+        while let Some(x) = self.next() {
+            f(x);
+        }
+    }
+
+    /// Calls a closure on each remaining partition until it returns
+    /// something, and returns that.
+    ///
+    /// This is like `find_map` on `into_iter`, but it only allocates if
+    /// the closure does.
+    #[inline]
+    pub fn find_map_slice<R, F: FnMut(&[T]) -> Option<R>>(mut self, mut f: F) -> Option<R> {
+        while let Some(x) = self.next() {
+            if let Some(r) = f(x) {
+                return Some(r);
+            }
+        }
+        None
+    }
+
+    /// Appends each remaining partition to `out`, reserving room for all
+    /// of them first if it's known how many there are.
+    #[inline]
+    pub fn collect_into(self, out: &mut Vec<Vec<T>>) {
+        let iter = self.into_iter();
+        out.reserve(iter.size_hint().1.unwrap_or(0));
+        out.extend(iter);
+    }
+
+    /// Turns the iterator into one over batches of `size` partitions,
+    /// except that the last one can be smaller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn chunks(self, size: usize) -> impl Iterator<Item = Vec<Vec<T>>> {
+        assert!(size != 0, "chunks can't be empty");
+
+        // Knowing how many are left means that each chunk is allocated
+        // once, at the right size.
+        let mut iter = self.into_iter();
+        core::iter::from_fn(move || {
+            let mut chunk = Vec::with_capacity(iter.size_hint().0.min(size));
+            chunk.extend(iter.by_ref().take(size));
+            if chunk.is_empty() {
+                None
+            } else {
+                Some(chunk)
+            }
+        })
+    }
+
     /// Destroys the iterator and returns a vector for further use.
     ///
     /// You only need to call this function if you want to reuse the
     /// vector for something else. Its contents will be in an undefined
     /// state, and so cannot be relied upon.
     #[inline]
-    pub fn end(self) -> Vec<usize> {
+    pub fn end(self) -> Vec<T> {
         self.a
     }
 }
 
+/// Moves on to the next partition in `a`, and returns its length.
+///
+/// This is shared by every iterator that uses Kelleher's method, since
+/// they only differ in where the parts are kept.
+#[inline]
+fn step<T: Part>(a: &mut [T], k: &mut usize, y: &mut usize, next: &mut State) -> Option<usize> {
+    match *next {
+        State::EmptyOnce => {
+            *next = State::A;
+            Some(0)
+        },
+        State::A => {
+            if *k == 0 {
+                None
+            } else {
+                *k -= 1;
+                let x = a[*k].to_usize() + 1;
+
+                // The loop can't underflow `y`, and it can't run off the
+                // end, since every part it writes is at least one.
+                while 2 * x <= *y {
+                    a[*k] = T::from_usize(x);
+                    *y -= x;
+                    *k += 1;
+                }
+
+                let l = *k + 1;
+
+                if x <= *y {
+                    debug_assert!(l < a.len(), "a partition has more parts than its sum");
+                    a[*k] = T::from_usize(x);
+                    a[l] = T::from_usize(*y);
+                    *next = State::B { x, l };
+                    Some(*k + 2)
+                } else {
+                    a[*k] = T::from_usize(x + *y);
+                    *y = x + *y - 1;
+                    Some(*k + 1)
+                }
+            }
+        },
+        State::B { mut x, l } => {
+            // Case B is only reached with `x <= y`, so `y` is positive.
+            debug_assert!(1 <= x && x <= *y && l == *k + 1);
+            x += 1;
+            *y -= 1;
+
+            if x <= *y {
+                a[*k] = T::from_usize(x);
+                a[l] = T::from_usize(*y);
+                *next = State::B { x, l };
+                Some(*k + 2)
+            } else {
+                a[*k] = T::from_usize(x + *y);
+                *y = x + *y - 1;
+                *next = State::A;
+                Some(*k + 1)
+            }
+        },
+    }
+}
+
 #[test]
 fn oeis() {
     //! Tests the first few entries of A000041.
@@ -153,3 +899,488 @@ fn oeis() {
         assert_eq!(c, n);
     }
 }
+
+#[test]
+fn for_each() {
+    //! Checks that `for_each` visits the same partitions as `next`.
+
+    let expected = collect!(Partitions::new(10));
+
+    let mut actual = Vec::new();
+    Partitions::new(10).for_each(|x| actual.push(x.to_vec()));
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn exhausted() {
+    //! Checks that `next` keeps returning `None` once it's finished.
+
+    for n in 0..6 {
+        let mut p = Partitions::new(n);
+        while p.next().is_some() {}
+
+        for _ in 0..3 {
+            assert!(p.next().is_none());
+        }
+    }
+
+    let mut p = Partitions::recycle(0, vec![1, 2, 3]);
+    assert_eq!(p.next(), Some(&[][..]));
+    for _ in 0..3 {
+        assert!(p.next().is_none());
+    }
+}
+
+#[test]
+fn empty() {
+    //! Checks that zero has exactly one partition, however the iterator
+    //! was made.
+
+    let iters = vec![
+        Partitions::new(0),
+        Partitions::recycle(0, Vec::new()),
+        Partitions::recycle(0, Vec::with_capacity(10)),
+        Partitions::recycle(0, vec![7; 5]),
+    ];
+
+    for mut p in iters {
+        assert_eq!(p.next(), Some(&[][..]));
+        for _ in 0..3 {
+            assert_eq!(p.next(), None);
+        }
+    }
+}
+
+#[test]
+fn boundaries() {
+    //! Checks the smallest cases exactly, and the sums and order of
+    //! every partition of the numbers up to 60.
+
+    let mut p = Partitions::new(0);
+    assert_eq!(p.next(), Some(&[][..]));
+    assert_eq!(p.next(), None);
+
+    let mut p = Partitions::new(1);
+    assert_eq!(p.next(), Some(&[1][..]));
+    assert_eq!(p.next(), None);
+    assert_eq!(p.next(), None);
+
+    for n in 0..61 {
+        let mut p = Partitions::new(n);
+        let mut c = 0;
+        while let Some(x) = p.next() {
+            assert_eq!(x.iter().sum::<usize>(), n);
+            assert!(x.first() != Some(&0));
+            assert!(x.windows(2).all(|w| w[0] <= w[1]));
+            c += 1;
+        }
+        assert_eq!(c, count(n));
+    }
+}
+
+#[test]
+fn reset() {
+    //! Checks that a reset iterator goes through the same partitions
+    //! again, whether or not it was finished.
+
+    for n in 0..12 {
+        let mut p = Partitions::new(n);
+        let first = collect!(p);
+
+        p.reset();
+        let second = collect!(p);
+
+        assert_eq!(first, second);
+
+        p.reset();
+        p.advance_by_partitions(first.len() / 2);
+        p.reset();
+        assert_eq!(p.into_iter().collect::<Vec<_>>(), first);
+    }
+}
+
+#[test]
+fn advance() {
+    //! Checks that advancing agrees with skipping the collected ones.
+
+    for n in 0..12 {
+        let all: Vec<Vec<usize>> = Partitions::new(n).into_iter().collect();
+
+        for count in 0..(all.len() + 3) {
+            let mut p = Partitions::new(n);
+            assert_eq!(p.advance_by_partitions(count), count.min(all.len()));
+
+            let rest: Vec<Vec<usize>> = p.into_iter().collect();
+            let expected: Vec<Vec<usize>> = all.iter().skip(count).cloned().collect();
+            assert_eq!(rest, expected);
+        }
+    }
+}
+
+#[test]
+fn cursor() {
+    //! Checks that splitting at a cursor and carrying on from it gives
+    //! back the whole sequence.
+
+    for n in 0..15 {
+        let all: Vec<Vec<usize>> = Partitions::new(n).into_iter().collect();
+
+        for split in 0..(all.len() + 1) {
+            let mut p = Partitions::new(n);
+            let mut joined = Vec::new();
+            for _ in 0..split {
+                joined.push(p.next().unwrap().to_vec());
+            }
+
+            let c = p.cursor().unwrap();
+            assert_eq!(c, Cursor::at(split as u64));
+            drop(p);
+
+            let mut q = Partitions::from_cursor(n, c);
+            while let Some(x) = q.next() {
+                joined.push(x.to_vec());
+            }
+            assert_eq!(q.cursor(), Some(Cursor::at(all.len() as u64)));
+            assert_eq!(joined, all);
+        }
+
+        for &i in &[all.len() as u64 + 1, all.len() as u64 + 10, u64::MAX] {
+            let mut p = Partitions::from_cursor(n, Cursor::at(i));
+            assert_eq!(p.cursor(), Some(Cursor::at(all.len() as u64)));
+            assert_eq!(p.next(), None);
+
+            let mut iter = Partitions::from_cursor(n, Cursor::at(i)).into_iter();
+            assert_eq!(iter.size_hint(), (0, Some(0)));
+            assert_eq!(iter.next(), None);
+            assert_eq!(iter.next_back(), None);
+        }
+    }
+}
+
+#[test]
+fn resume_after() {
+    //! Checks that resuming after each partition gives the rest of
+    //! them, and that bad partitions are caught.
+
+    for n in 0..15 {
+        let all: Vec<Vec<usize>> = Partitions::new(n).into_iter().collect();
+
+        for (i, x) in all.iter().enumerate() {
+            let p = Partitions::resume_after(n, x).unwrap();
+            assert_eq!(p.cursor(), Some(Cursor::at(i as u64 + 1)));
+            assert_eq!(p.into_iter().collect::<Vec<_>>(), &all[(i + 1)..]);
+        }
+    }
+
+    assert!(Partitions::resume_after(4, &[1, 2]).is_none());
+    assert!(Partitions::resume_after(4, &[3, 1]).is_none());
+    assert!(Partitions::resume_after(4, &[0, 4]).is_none());
+    assert!(Partitions::resume_after(1, &[]).is_none());
+
+    // Past `MAX_RANK` the position is unknown, but resuming still works.
+    let n = MAX_RANK + 1;
+    let mut p = Partitions::resume_after(n, &[1; MAX_RANK + 1]).unwrap();
+    assert_eq!(p.cursor(), None);
+    assert_eq!(p.num_parts(), n);
+    let mut expected = vec![1; n - 2];
+    expected.push(2);
+    assert_eq!(p.next(), Some(&expected[..]));
+    assert_eq!(p.cursor(), None);
+
+    let mut iter = Partitions::resume_after(n, &[n]).unwrap().into_iter();
+    assert_eq!(iter.size_hint(), (0, None));
+    assert_eq!(iter.next(), None);
+
+    let p = Partitions::resume_after(1000, &[1; 1000]).unwrap();
+    assert_eq!(p.into_iter().take(3).count(), 3);
+}
+
+#[test]
+fn ranges() {
+    //! Checks that the shards are even, and that their partitions add up
+    //! to all of them without overlapping.
+
+    for n in 0..15 {
+        let all: Vec<Vec<usize>> = Partitions::new(n).into_iter().collect();
+
+        for shards in 1..(all.len() + 3) {
+            let ranges = split_ranges(n, shards);
+            assert_eq!(ranges.len(), shards);
+
+            let mut joined = Vec::new();
+            for &(start, end) in &ranges {
+                assert!(end - start <= (all.len() / shards) as u64 + 1);
+
+                let mut p = Partitions::range(n, start, end);
+                while let Some(x) = p.next() {
+                    joined.push(x.to_vec());
+                }
+            }
+            assert_eq!(joined, all);
+        }
+    }
+
+    let mut p = Partitions::range(10, 40, 100);
+    let mut c = 0;
+    while p.next().is_some() {
+        c += 1;
+    }
+    assert_eq!(c, 2);
+
+    // The positions don't fit in 32 bits by the end.
+    let ranges = split_ranges(MAX_RANK, 3);
+    let end = ranges[2].1;
+    assert_eq!(end as u128, count(MAX_RANK));
+
+    let mut p = Partitions::range(MAX_RANK, end - 2, end);
+    assert!(p.next().is_some());
+    assert_eq!(p.next(), Some(&[MAX_RANK][..]));
+    assert_eq!(p.next(), None);
+}
+
+#[test]
+fn num_parts() {
+    //! Checks that `num_parts` matches the length of each partition.
+
+    for n in 0..20 {
+        let mut p = Partitions::new(n);
+        assert_eq!(p.num_parts(), 0);
+
+        let mut last = 0;
+        while let Some(x) = p.next() {
+            last = x.len();
+            assert_eq!(p.num_parts(), last);
+        }
+
+        assert_eq!(p.num_parts(), last);
+    }
+}
+
+#[test]
+fn extremes() {
+    //! Checks the largest and smallest parts against the slices.
+
+    for n in 0..20 {
+        let mut p = Partitions::<u8>::of(n);
+        assert_eq!(p.largest_part(), None);
+        assert_eq!(p.smallest_part(), None);
+
+        while let Some(x) = p.next() {
+            let x: Vec<usize> = x.iter().map(|&y| y as usize).collect();
+            assert_eq!(p.largest_part(), x.iter().cloned().max());
+            assert_eq!(p.smallest_part(), x.iter().cloned().min());
+        }
+    }
+}
+
+#[test]
+fn count_of_part() {
+    //! Compares against counting the parts in the slices.
+
+    for n in 0..20 {
+        let mut p = Partitions::<u8>::of(n);
+        assert_eq!(p.count_of_part(1), 0);
+
+        while let Some(x) = p.next() {
+            let x: Vec<usize> = x.iter().map(|&y| y as usize).collect();
+            for value in 0..(n + 2) {
+                let c = x.iter().filter(|&&y| y == value).count();
+                assert_eq!(p.count_of_part(value), c);
+            }
+        }
+    }
+}
+
+#[test]
+fn find_map_slice() {
+    //! Checks that searching finds the same partition as `find`.
+
+    let is_square = |x: &[usize]| x.len() >= 3 && x.iter().all(|&y| y == x.len());
+
+    for n in 0..20 {
+        let expected = Partitions::new(n).into_iter().find(|x| is_square(x));
+        let actual = Partitions::new(n)
+            .find_map_slice(|x| if is_square(x) { Some(x.to_vec()) } else { None });
+        assert_eq!(actual, expected);
+        assert_eq!(actual.is_some(), n == 9 || n == 16);
+    }
+
+    assert_eq!(Partitions::new(10).find_map_slice(|x| x.iter().position(|&y| y == 3)), Some(7));
+}
+
+#[test]
+fn collect_into() {
+    //! Checks that collecting twice into one vector concatenates.
+
+    let mut out = Vec::new();
+    Partitions::new(5).collect_into(&mut out);
+
+    let mut p = Partitions::new(7);
+    p.advance_by_partitions(3);
+    p.collect_into(&mut out);
+
+    let mut expected: Vec<Vec<usize>> = Partitions::new(5).into_iter().collect();
+    expected.extend(Partitions::new(7).into_iter().skip(3));
+    assert_eq!(out, expected);
+    assert_eq!(out.len(), 7 + 15 - 3);
+
+    // There are too many partitions of 500 to count, so only the last
+    // few are collected.
+    let mut tail = vec![vec![500]];
+    for _ in 0..5 {
+        let x = prev_partition(tail.last().unwrap()).unwrap();
+        tail.push(x);
+    }
+    tail.reverse();
+
+    let mut out = Vec::new();
+    Partitions::resume_after(500, &tail[0]).unwrap().collect_into(&mut out);
+    assert_eq!(out, &tail[1..]);
+}
+
+#[test]
+fn chunks() {
+    //! Checks the chunk sizes, and that the chunks add up to all of the
+    //! partitions.
+
+    for n in 0..12 {
+        let all: Vec<Vec<usize>> = Partitions::new(n).into_iter().collect();
+
+        for size in 1..(all.len() + 2) {
+            let chunks: Vec<Vec<Vec<usize>>> = Partitions::new(n).chunks(size).collect();
+            assert_eq!(chunks.len(), all.len().div_ceil(size));
+
+            for (i, chunk) in chunks.iter().enumerate() {
+                assert_eq!(chunk.len(), size.min(all.len() - i * size));
+            }
+
+            assert_eq!(chunks.concat(), all);
+        }
+    }
+
+    let mut chunks = Partitions::new(500).chunks(4);
+    let first = chunks.next().unwrap();
+    assert_eq!(first.len(), 4);
+    assert_eq!(first[0], vec![1; 500]);
+
+    // Without a known length, the last chunk still stops in time.
+    let mut tail = vec![vec![500]];
+    for _ in 0..9 {
+        let x = prev_partition(tail.last().unwrap()).unwrap();
+        tail.push(x);
+    }
+    tail.reverse();
+
+    let p = Partitions::resume_after(500, &tail[0]).unwrap();
+    let chunks: Vec<Vec<Vec<usize>>> = p.chunks(4).collect();
+    assert_eq!(chunks.iter().map(|x| x.len()).collect::<Vec<_>>(), [4, 4, 1]);
+    assert_eq!(chunks.concat(), &tail[1..]);
+}
+
+#[test]
+fn debug() {
+    //! Checks that the debug output shows the number of parts, and
+    //! doesn't move the iterator.
+
+    let mut p = Partitions::new(6);
+    p.advance_by_partitions(4);
+
+    let s = format!("{:?}", p);
+    assert!(s.starts_with("Partitions { n: 6, parts: 4,"), "{}", s);
+    assert_eq!(format!("{:?}", p), s);
+    assert_eq!(p.next(), Some(&[1, 1, 4][..]));
+}
+
+#[test]
+fn clone() {
+    //! Checks that a clone carries on independently of the original.
+
+    let mut p = Partitions::new(15);
+    for _ in 0..40 {
+        p.next();
+    }
+
+    let mut q = p.clone();
+    let mut a = Vec::new();
+    let mut b = Vec::new();
+
+    // Advance them unevenly, so that they'd interfere if they shared.
+    while let Some(x) = p.next() {
+        a.push(x.to_vec());
+        if let Some(y) = q.next() {
+            b.push(y.to_vec());
+        }
+        if let Some(y) = q.next() {
+            b.push(y.to_vec());
+        }
+    }
+    while let Some(y) = q.next() {
+        b.push(y.to_vec());
+    }
+
+    assert_eq!(a, b);
+    assert_eq!(a.len(), 176 - 40);
+}
+
+#[test]
+fn part_types() {
+    //! Checks that smaller part types give the same partitions.
+
+    let mut expected = Vec::new();
+    Partitions::new(30).for_each(|x| expected.push(x.to_vec()));
+
+    let mut actual = Vec::new();
+    Partitions::<u8>::of(30).for_each(|x| {
+        actual.push(x.iter().map(|&y| y as usize).collect::<Vec<_>>());
+    });
+
+    assert_eq!(actual, expected);
+    assert_eq!(Partitions::<u8>::of(255).next(), Some(&[1; 255][..]));
+}
+
+#[test]
+fn try_new() {
+    //! Checks that numbers that are too big give errors instead of
+    //! panicking or aborting.
+
+    for &n in &[usize::MAX, usize::MAX / 2] {
+        assert_eq!(Partitions::try_new(n).err(), Some(PartitionsError::TooBig(n)));
+    }
+    assert_eq!(Partitions::<u8>::try_of(256).err(), Some(PartitionsError::PartTooSmall(256)));
+
+    let mut p = Partitions::try_new(3).unwrap();
+    assert_eq!(p.next(), Some(&[1, 1, 1][..]));
+    assert_eq!(Partitions::<u8>::try_of(255).unwrap().next(), Some(&[1; 255][..]));
+}
+
+#[test]
+#[should_panic]
+fn part_overflow() {
+    //! Checks that a number too big for the part type is caught.
+
+    Partitions::<u8>::of(256);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde() {
+    //! Checks that a deserialized iterator carries on where it was.
+
+    let mut expected = Vec::new();
+    Partitions::new(12).for_each(|x| expected.push(x.to_vec()));
+
+    let mut p = Partitions::new(12);
+    let mut actual = Vec::new();
+    for _ in 0..5 {
+        actual.push(p.next().unwrap().to_vec());
+    }
+
+    let json = serde_json::to_string(&p).unwrap();
+    drop(p);
+
+    let p: Partitions = serde_json::from_str(&json).unwrap();
+    p.for_each(|x| actual.push(x.to_vec()));
+
+    assert_eq!(actual, expected);
+}
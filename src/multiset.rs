@@ -0,0 +1,210 @@
+//! Partitions of multisets.
+
+use alloc::vec::Vec;
+
+/// Iterates over the partitions of a multiset into unordered,
+/// non-empty blocks.
+///
+/// The multiset is given by how many copies there are of each element,
+/// and each block is given the same way, so a vector like `[2, 1]`
+/// stands for `{a, a, b}`. This is Algorithm M from section 7.2.1.5 of
+/// The Art of Computer Programming, and the partitions come out in
+/// decreasing lexicographic order of their blocks.
+pub struct MultisetPartitions {
+    /// The number of different elements, including missing ones.
+    dims: usize,
+    /// The original index of each element that's present.
+    elems: Vec<usize>,
+    /// Element indices, remaining multiplicities and block multiplicities
+    /// of the entries in each stack frame.
+    c: Vec<usize>,
+    u: Vec<usize>,
+    v: Vec<usize>,
+    /// Where each stack frame starts.
+    f: Vec<usize>,
+    a: usize,
+    b: usize,
+    l: usize,
+    state: State,
+}
+
+enum State {
+    Start,
+    Running,
+    Done,
+}
+
+impl MultisetPartitions {
+    /// Makes an iterator over the partitions of the multiset with
+    /// `counts[i]` copies of the `i`th element.
+    pub fn new(counts: &[usize]) -> MultisetPartitions {
+        let elems: Vec<usize> = (0..counts.len()).filter(|&i| counts[i] > 0).collect();
+        let m = elems.len();
+        let n: usize = counts.iter().sum();
+        let size = m * n + 1;
+
+        let mut p = MultisetPartitions {
+            dims: counts.len(),
+            elems,
+            c: vec![0; size],
+            u: vec![0; size],
+            v: vec![0; size],
+            f: vec![0; n + 2],
+            a: 0,
+            b: m,
+            l: 0,
+            state: State::Start,
+        };
+
+        for j in 0..m {
+            p.c[j] = j;
+            p.u[j] = counts[p.elems[j]];
+            p.v[j] = p.u[j];
+        }
+        p.f[1] = m;
+
+        p
+    }
+
+    /// Pushes stack frames for whatever hasn't been put in a block yet,
+    /// taking as much as possible each time.
+    fn descend(&mut self) {
+        loop {
+            let (mut j, mut k, mut x) = (self.a, self.b, false);
+
+            while j < self.b {
+                self.u[k] = self.u[j] - self.v[j];
+                if self.u[k] == 0 {
+                    x = true;
+                } else {
+                    self.c[k] = self.c[j];
+                    if x {
+                        self.v[k] = self.u[k];
+                    } else {
+                        self.v[k] = self.v[j].min(self.u[k]);
+                        x = self.u[k] < self.v[j];
+                    }
+                    k += 1;
+                }
+                j += 1;
+            }
+
+            if k == self.b {
+                break;
+            }
+
+            self.a = self.b;
+            self.b = k;
+            self.l += 1;
+            self.f[self.l + 1] = self.b;
+        }
+    }
+
+    /// Makes the last block that can shrink smaller, popping frames as
+    /// needed, and returns whether there was one.
+    fn shrink(&mut self) -> bool {
+        loop {
+            let mut j = self.b - 1;
+            while self.v[j] == 0 {
+                j -= 1;
+            }
+
+            if j == self.a && self.v[j] == 1 {
+                if self.l == 0 {
+                    return false;
+                }
+
+                self.l -= 1;
+                self.b = self.a;
+                self.a = self.f[self.l];
+            } else {
+                self.v[j] -= 1;
+                for k in (j + 1)..self.b {
+                    self.v[k] = self.u[k];
+                }
+                return true;
+            }
+        }
+    }
+
+    fn visit(&self) -> Vec<Vec<usize>> {
+        (0..(self.l + 1))
+            .map(|i| {
+                let mut block = vec![0; self.dims];
+                for t in self.f[i]..self.f[i + 1] {
+                    block[self.elems[self.c[t]]] = self.v[t];
+                }
+                block
+            })
+            .collect()
+    }
+}
+
+impl Iterator for MultisetPartitions {
+    type Item = Vec<Vec<usize>>;
+
+    fn next(&mut self) -> Option<Vec<Vec<usize>>> {
+        match self.state {
+            State::Start => {
+                if self.elems.is_empty() {
+                    self.state = State::Done;
+                    return Some(Vec::new());
+                }
+
+                self.state = State::Running;
+            },
+            State::Running => {
+                if !self.shrink() {
+                    self.state = State::Done;
+                    return None;
+                }
+            },
+            State::Done => return None,
+        }
+
+        self.descend();
+        Some(self.visit())
+    }
+}
+
+#[test]
+fn small() {
+    //! Checks the partitions of `{a, a, b}` and some known counts.
+
+    let all: Vec<_> = MultisetPartitions::new(&[2, 1]).collect();
+    assert_eq!(all, vec![
+        vec![vec![2, 1]],
+        vec![vec![2, 0], vec![0, 1]],
+        vec![vec![1, 1], vec![1, 0]],
+        vec![vec![1, 0], vec![1, 0], vec![0, 1]],
+    ]);
+
+    // The Bell numbers, the integer partitions, and A020555.
+    assert_eq!(MultisetPartitions::new(&[1; 5]).count(), 52);
+    assert_eq!(MultisetPartitions::new(&[12]).count(), 77);
+    assert_eq!(MultisetPartitions::new(&[2, 2]).count(), 9);
+    assert_eq!(MultisetPartitions::new(&[2, 2, 2]).count(), 66);
+
+    assert_eq!(MultisetPartitions::new(&[]).count(), 1);
+    assert_eq!(MultisetPartitions::new(&[0, 2, 0]).collect::<Vec<_>>(), vec![
+        vec![vec![0, 2, 0]],
+        vec![vec![0, 1, 0], vec![0, 1, 0]],
+    ]);
+}
+
+#[test]
+fn blocks() {
+    //! Checks that the blocks are non-empty and add up to the multiset.
+
+    let counts = [3, 1, 2];
+    for p in MultisetPartitions::new(&counts) {
+        let mut sum = [0; 3];
+        for block in &p {
+            assert!(block.iter().any(|&x| x > 0));
+            for (s, &x) in sum.iter_mut().zip(block) {
+                *s += x;
+            }
+        }
+        assert_eq!(sum, counts);
+    }
+}
@@ -0,0 +1,1252 @@
+//! Partitions in orders other than the usual one.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::iter;
+
+/// Iterates over partitions in reverse lexicographic order.
+///
+/// This is made by `Partitions::lexicographic`. The parts of each
+/// partition are in descending order, and each partition compares
+/// lexicographically less than the one before it, so the partitions
+/// of 4 are `[4], [3, 1], [2, 2], [2, 1, 1], [1, 1, 1, 1]`.
+pub struct Lexicographic {
+    a: Vec<usize>,
+    n: usize,
+    started: bool,
+}
+
+impl Lexicographic {
+    #[inline]
+    pub(crate) fn new(n: usize) -> Lexicographic {
+        Lexicographic {
+            a: Vec::with_capacity(n),
+            n,
+            started: false,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        if !self.started {
+            self.started = true;
+            if self.n > 0 {
+                self.a.push(self.n);
+            }
+            return Some(&self.a);
+        }
+
+        // The trailing ones join the unit taken from the last part that
+        // can shrink, and are then packed back into parts no bigger.
+        let mut r = 0;
+        while self.a.last() == Some(&1) {
+            self.a.pop();
+            r += 1;
+        }
+
+        let x = match self.a.last_mut() {
+            Some(x) => {
+                *x -= 1;
+                *x
+            },
+            None => return None,
+        };
+
+        r += 1;
+        while r > 0 {
+            let y = x.min(r);
+            self.a.push(y);
+            r -= y;
+        }
+
+        Some(&self.a)
+    }
+}
+
+/// A unit moved from one part to another, as reported by `Gray::moves`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    /// The part that the unit was taken from, which disappears if it
+    /// was a one.
+    pub from: usize,
+    /// The part that the unit was added to, or zero if the unit became
+    /// a new part.
+    pub to: usize,
+}
+
+/// Iterates over partitions in a Gray code order.
+///
+/// This is made by `Partitions::gray`. The parts of each partition are
+/// in descending order, and each partition can be made from the one
+/// before it by moving a single unit from one part to another. The
+/// first partition is all ones, and the last is `[n]`.
+///
+/// The order is put together the way Savage's is. The partitions with
+/// no part bigger than `k` are split up by their biggest part, and the
+/// pieces are walked one after another, some of them backwards, so
+/// that each one ends next to where the next one starts. The pieces
+/// are split up again in the same way, except for a few small ones
+/// that don't fit, which are searched for instead. All of this is
+/// planned out when the iterator is made, and the `O(n^2)` pieces stay
+/// in memory while it's walked, which comes to a few hundred megabytes
+/// by `n = 1000`.
+pub struct Gray {
+    /// The pieces, each planned once and shared by the ones that use
+    /// it. The last one is the whole order.
+    orders: Vec<Order>,
+    /// The pieces being walked, outermost first.
+    stack: Vec<Frame>,
+    /// The parts that the pieces being walked put in front.
+    head: Vec<usize>,
+    a: Vec<usize>,
+    /// The partition before `a`.
+    b: Vec<usize>,
+    started: bool,
+    moves: Vec<Move>,
+}
+
+/// A piece of a `Gray` order, which goes through some of the
+/// partitions of `m` with no part bigger than `k`.
+///
+/// Every piece starts at all ones, and the kind of piece says where it
+/// ends. Some pieces also leave out the top, which is the partition
+/// `k, k, ..., k, r`, and some leave out the top and the partition
+/// made from it by moving a unit from its last `k` into a new part,
+/// when `k` divides `m`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum End {
+    /// Ends at the top.
+    Top,
+    /// Ends at the hook `k, 1, ..., 1`.
+    Hook,
+    /// Ends at the top of the partitions with no part bigger than
+    /// `k - 1`.
+    Below,
+    /// Ends at `2, 2, 1, ..., 1`, with `k` always three.
+    Pairs,
+}
+
+/// The pieces that are small enough to be searched for instead of
+/// split up.
+const SMALL: usize = 8;
+
+/// The pieces that are small enough to be searched for if they can't
+/// be split up.
+const LARGE: usize = 70;
+
+/// A piece of a `Gray` order, as it's walked.
+enum Order {
+    /// Stretches of other pieces, one after another.
+    Plan {
+        segs: Vec<Seg>,
+        len: u128,
+        /// The biggest part there can be.
+        k: usize,
+    },
+    /// The partitions of `m` into twos and ones, with fewer ones each
+    /// time.
+    Twos(usize),
+}
+
+/// A stretch of a piece.
+enum Seg {
+    /// A single partition, as runs of equal parts.
+    One(Vec<(usize, usize)>),
+    /// `len` partitions of another piece, starting at `at`, backwards
+    /// if `rev`, and with a run of equal parts, `head`, put in front of
+    /// each of them.
+    Sub {
+        order: usize,
+        head: (usize, usize),
+        at: u128,
+        len: u128,
+        rev: bool,
+    },
+}
+
+/// A piece that's being walked.
+struct Frame {
+    order: usize,
+    /// The stretch that `at` is in, and where it starts.
+    seg: usize,
+    start: u128,
+    /// The next partition, counting from the start of the piece.
+    at: u128,
+    /// How many partitions are left, going backwards if `rev`.
+    left: u128,
+    rev: bool,
+    /// How many of `Gray::head` this put in front.
+    head: usize,
+}
+
+impl Gray {
+    pub(crate) fn new(n: usize) -> Gray {
+        let mut plans = Plans {
+            orders: Vec::new(),
+            done: BTreeMap::new(),
+            wanted: None,
+        };
+
+        // The plans don't always work out for the pieces, but they do
+        // for the whole order.
+        let root = plans.make(End::Top, 0, n, n).unwrap();
+        let mut gray = Gray {
+            orders: plans.orders,
+            stack: Vec::new(),
+            head: Vec::new(),
+            a: Vec::with_capacity(n),
+            b: Vec::with_capacity(n),
+            started: false,
+            moves: Vec::with_capacity(1),
+        };
+
+        let len = gray.orders[root].len();
+        gray.enter(root, (0, 0), 0, len, false);
+        gray
+    }
+
+    /// Returns the move that turned the previous partition into the
+    /// current one.
+    ///
+    /// There's exactly one, except before the first partition, when
+    /// there are none. This borrows the iterator, so it can't be called
+    /// while the partition from `next` is still in use. Copy what's
+    /// needed from the partition before asking for the move.
+    #[inline]
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        self.moves.clear();
+        core::mem::swap(&mut self.a, &mut self.b);
+
+        if !self.walk() {
+            return None;
+        }
+
+        if self.started {
+            self.moves.push(shift(&self.b, &self.a));
+        }
+        self.started = true;
+        Some(&self.a)
+    }
+
+    /// Writes the next partition into `a`, or returns false if there
+    /// are none left.
+    fn walk(&mut self) -> bool {
+        loop {
+            let f = match self.stack.last_mut() {
+                Some(f) => f,
+                None => return false,
+            };
+
+            if f.left == 0 {
+                let len = self.head.len() - f.head;
+                self.head.truncate(len);
+                self.stack.pop();
+                continue;
+            }
+
+            let segs = match self.orders[f.order] {
+                Order::Plan { ref segs, .. } => segs,
+                Order::Twos(m) => {
+                    let j = f.at as usize;
+                    self.a.clear();
+                    self.a.extend_from_slice(&self.head);
+                    self.a.extend(iter::repeat_n(2, j).chain(iter::repeat_n(1, m - 2 * j)));
+                    f.step(1);
+                    return true;
+                },
+            };
+
+            // Only the neighbouring stretch can be next, but some are
+            // empty.
+            while f.at < f.start {
+                f.seg -= 1;
+                f.start -= segs[f.seg].len();
+            }
+            while f.at - f.start >= segs[f.seg].len() {
+                f.start += segs[f.seg].len();
+                f.seg += 1;
+            }
+
+            match segs[f.seg] {
+                Seg::One(ref runs) => {
+                    self.a.clear();
+                    self.a.extend_from_slice(&self.head);
+                    expand(runs, &mut self.a);
+                    f.step(1);
+                    return true;
+                },
+                Seg::Sub { order, head, at, len, rev } => {
+                    let i = f.at - f.start;
+                    let n = f.left.min(if f.rev { i + 1 } else { len - i });
+                    let at = if rev { at + len - 1 - i } else { at + i };
+                    let rev = rev != f.rev;
+                    f.step(n);
+                    self.enter(order, head, at, n, rev);
+                },
+            }
+        }
+    }
+
+    fn enter(&mut self, order: usize, head: (usize, usize), at: u128, left: u128, rev: bool) {
+        let mut seg = 0;
+        let mut start = 0;
+        if let Order::Plan { ref segs, .. } = self.orders[order] {
+            while at - start >= segs[seg].len() {
+                start += segs[seg].len();
+                seg += 1;
+            }
+        }
+
+        self.head.extend(iter::repeat_n(head.0, head.1));
+        self.stack.push(Frame {
+            order,
+            seg,
+            start,
+            at,
+            left,
+            rev,
+            head: head.1,
+        });
+    }
+}
+
+impl Frame {
+    /// Moves past `n` partitions.
+    fn step(&mut self, n: u128) {
+        self.left -= n;
+        self.at = if self.rev {
+            self.at.wrapping_sub(n)
+        } else {
+            self.at + n
+        };
+    }
+}
+
+impl Order {
+    fn len(&self) -> u128 {
+        match *self {
+            Order::Plan { len, .. } => len,
+            Order::Twos(m) => (m / 2 + 1) as u128,
+        }
+    }
+}
+
+impl Seg {
+    fn len(&self) -> u128 {
+        match *self {
+            Seg::One(_) => 1,
+            Seg::Sub { len, .. } => len,
+        }
+    }
+
+    /// Leaves out the first `n` partitions.
+    fn skip(mut self, n: u128) -> Seg {
+        if let Seg::Sub { ref mut at, ref mut len, rev, .. } = self {
+            if !rev {
+                *at += n;
+            }
+            *len -= n;
+        }
+        self
+    }
+
+    /// Leaves out the last `n` partitions.
+    fn trim(mut self, n: u128) -> Seg {
+        if let Seg::Sub { ref mut at, ref mut len, rev, .. } = self {
+            if rev {
+                *at += n;
+            }
+            *len -= n;
+        }
+        self
+    }
+
+    fn rev(mut self) -> Seg {
+        if let Seg::Sub { ref mut rev, .. } = self {
+            *rev = !*rev;
+        }
+        self
+    }
+
+    /// Puts a part in front of each partition, which has to be the same
+    /// as any that are already there.
+    fn head(mut self, x: usize) -> Seg {
+        if let Seg::Sub { ref mut head, .. } = self {
+            debug_assert!(head.1 == 0 || head.0 == x);
+            *head = (x, head.1 + 1);
+        }
+        self
+    }
+}
+
+/// A kind of piece, as its end, its cut, `m` and `k`.
+type Kind = (End, u8, usize, usize);
+
+/// Plans the pieces of a `Gray` order, each one only once.
+struct Plans {
+    orders: Vec<Order>,
+    /// The piece for each kind that's been planned, or nothing if there
+    /// isn't one.
+    done: BTreeMap<Kind, Option<usize>>,
+    /// A kind that a plan needed before it had been planned.
+    wanted: Option<Kind>,
+}
+
+impl Plans {
+    /// Plans the piece of a kind that leaves out `cut` partitions, and
+    /// the pieces it's made of, or returns nothing if there isn't one.
+    ///
+    /// The pieces are planned from a list rather than by recursing,
+    /// since there are pieces inside pieces about `n` deep. When a plan
+    /// needs a piece that hasn't been planned yet, that piece goes on
+    /// the list above it, and the plan is made again once it's done.
+    /// Each piece is only made of ones with a smaller `m`, or the same
+    /// `m` and a smaller `k`, so the list always runs out.
+    fn make(&mut self, end: End, cut: u8, m: usize, k: usize) -> Option<usize> {
+        let kind = normalize(end, cut, m, k)?;
+        let mut todo = vec![kind];
+        while let Some(&(end, cut, m, k)) = todo.last() {
+            let order = self.plan(end, cut, m, k);
+            if let Some(wanted) = self.wanted.take() {
+                todo.push(wanted);
+                continue;
+            }
+
+            let id = order.map(|order| {
+                self.orders.push(order);
+                self.orders.len() - 1
+            });
+            self.done.insert((end, cut, m, k), id);
+            todo.pop();
+        }
+
+        self.done[&kind]
+    }
+
+    /// Looks up the piece of a kind that leaves out `cut` partitions, or
+    /// returns nothing if there isn't one, or if it hasn't been planned
+    /// yet, in which case it's `wanted`.
+    fn get(&mut self, end: End, cut: u8, m: usize, k: usize) -> Option<usize> {
+        let kind = normalize(end, cut, m, k)?;
+        match self.done.get(&kind) {
+            Some(&id) => id,
+            None => {
+                self.wanted.get_or_insert(kind);
+                None
+            },
+        }
+    }
+
+    fn plan(&mut self, end: End, cut: u8, m: usize, k: usize) -> Option<Order> {
+        if end == End::Top && (k <= 1 || m <= 1) {
+            return Some(plan(vec![one(&[(1, m)])], k));
+        }
+        if end == End::Top && k == 2 {
+            return Some(Order::Twos(m));
+        }
+
+        let all = vertices(cut, m, k);
+        if let Some(ref all) = all {
+            if all.len() <= SMALL {
+                return search(all, end, m, k).map(|segs| plan(segs, k));
+            }
+        }
+
+        let segs = match (end, cut) {
+            (End::Top, _) => self.top(m, k).or_else(|| self.split(m, k)),
+            (End::Hook, 1) => self.hook(cut, m, k).or_else(|| self.hook_short(m, k)),
+            (End::Hook, _) => self.hook(cut, m, k),
+            (End::Below, 2) => self.below(cut, m, k),
+            (End::Below, _) => self.gadget(cut, m, k)
+                .or_else(|| if cut == 1 { self.below_short(m, k) } else { None })
+                .or_else(|| self.below(cut, m, k)),
+            (End::Pairs, _) => self.pairs(cut, m),
+        };
+
+        match (segs, all) {
+            _ if self.wanted.is_some() => None,
+            (Some(segs), _) => Some(plan(segs, k)),
+            (None, Some(all)) => search(&all, end, m, k).map(|segs| plan(segs, k)),
+            (None, None) => None,
+        }
+    }
+
+    fn all(&self, order: usize) -> Seg {
+        Seg::Sub {
+            order,
+            head: (0, 0),
+            at: 0,
+            len: self.orders[order].len(),
+            rev: false,
+        }
+    }
+
+    /// Splits the partitions up by their biggest part, when they all
+    /// meet end to end.
+    fn top(&mut self, m: usize, k: usize) -> Option<Vec<Seg>> {
+        if k == 3 {
+            if m < 6 {
+                return None;
+            }
+
+            let twos = self.get(End::Top, 0, m, 2)?;
+            let less = self.get(End::Top, 0, m - 3, 2)?;
+            let rest = self.get(End::Top, 0, m - 6, 3)?;
+            return Some(vec![
+                one(&[(1, m)]),
+                one(&[(2, 1), (1, m - 2)]),
+                one(&[(3, 1), (1, m - 3)]),
+                one(&[(3, 1), (2, 1), (1, m - 5)]),
+                self.all(twos).skip(2),
+                self.all(less).skip(2).rev().head(3),
+                self.all(rest).head(3).head(3),
+            ]);
+        }
+
+        if m.is_multiple_of(k - 1) {
+            return None;
+        }
+
+        let low = self.get(End::Hook, 0, m, k - 1)?;
+        let rest = self.get(End::Top, 0, m - k, k)?;
+        Some(vec![self.all(low), self.all(rest).head(k)])
+    }
+
+    /// Splits the partitions up like `top`, but when `k - 1` divides
+    /// `m`, so the two partitions that the hook leaves out are visited
+    /// partway through the last piece.
+    fn split(&mut self, m: usize, k: usize) -> Option<Vec<Seg>> {
+        if k < 4 || !m.is_multiple_of(k - 1) || k >= m {
+            return None;
+        }
+
+        let rest = self.get(End::Top, 0, m - k, k)?;
+        let mut x = Vec::new();
+        expand(&top(m - k, k - 1), &mut x);
+        let i = self.find(rest, &x)?;
+        if i == 0 || self.first(rest, i - 1) > k - 1 {
+            return None;
+        }
+
+        let low = self.get(End::Hook, 2, m, k - 1)?;
+        let len = self.orders[rest].len();
+        Some(vec![
+            self.all(low),
+            self.all(rest).trim(len - i).head(k),
+            one(&second(m, k - 1)),
+            one(&top(m, k - 1)),
+            self.all(rest).skip(i).head(k),
+        ])
+    }
+
+    fn hook(&mut self, cut: u8, m: usize, c: usize) -> Option<Vec<Seg>> {
+        let low = self.get(End::Top, 0, m, c - 1)?;
+        let rest = if cut == 2 && m == 2 * c {
+            self.get(End::Top, 0, c, c - 2)?
+        } else {
+            self.get(End::Below, cut, m - c, c)?
+        };
+
+        Some(vec![self.all(low), self.all(rest).rev().head(c)])
+    }
+
+    /// Makes a hook without the top when there are only a few parts
+    /// bigger than half of `m` to get through.
+    fn hook_short(&mut self, m: usize, c: usize) -> Option<Vec<Seg>> {
+        let n = m - c;
+        if m == 2 * c - 1 {
+            let low = self.get(End::Top, 0, m, c - 1)?;
+            let rest = self.get(End::Top, 0, n, n)?;
+            return Some(vec![self.all(low), self.all(rest).trim(1).rev().head(c)]);
+        }
+
+        if 2 * c < m + 5 {
+            return None;
+        }
+
+        let low = self.get(End::Top, 0, m, c - 3)?;
+        let far = self.get(End::Top, 0, n + 2, n + 2)?;
+        let mid = self.get(End::Top, 0, n + 1, n + 1)?;
+        let near = self.get(End::Top, 0, n, n)?;
+        Some(vec![
+            self.all(low),
+            one(&[(c - 2, 1), (n + 2, 1)]),
+            one(&[(c - 1, 1), (n + 1, 1)]),
+            one(&[(c - 2, 1), (n + 1, 1), (1, 1)]),
+            self.all(far).rev().skip(2).head(c - 2),
+            self.all(mid).trim(1).head(c - 1),
+            self.all(near).trim(1).rev().head(c),
+        ])
+    }
+
+    fn below(&mut self, cut: u8, m: usize, c: usize) -> Option<Vec<Seg>> {
+        if c == 3 {
+            let rest = self.get(End::Pairs, cut, m - 3, 3)?;
+            let twos = self.get(End::Top, 0, m, 2)?;
+            return Some(vec![
+                one(&[(1, m)]),
+                one(&[(2, 1), (1, m - 2)]),
+                one(&[(2, 2), (1, m - 4)]),
+                self.all(rest).head(3),
+                self.all(twos).skip(3),
+            ]);
+        }
+
+        let low = self.get(End::Hook, 1, m, c - 1)?;
+        let rest = if cut == 2 && m == 2 * c {
+            self.get(End::Top, 0, c, c - 2)?
+        } else {
+            self.get(End::Below, cut, m - c, c)?
+        };
+
+        Some(vec![self.all(low), self.all(rest).head(c), one(&top(m, c - 1))])
+    }
+
+    /// Makes the partitions that end below `c` when only the last few
+    /// kinds of biggest part are more than half of `m`, so that their
+    /// pieces can be threaded through each other.
+    fn gadget(&mut self, cut: u8, m: usize, c: usize) -> Option<Vec<Seg>> {
+        if 2 * c < m + 3 || m < c + 2 {
+            return None;
+        }
+
+        let n = m - c;
+        let near = self.get(End::Top, 0, n, n)?;
+        let mid = self.get(End::Top, 0, n + 1, n + 1)?;
+        let middle = [
+            one(&hook(m, c - 2)),
+            one(&hook(m, c - 1)),
+            self.all(near).trim(cut as u128).head(c),
+            self.all(mid).skip(1).trim(1).rev().head(c - 1),
+        ];
+
+        if !m.is_multiple_of(c - 3) {
+            let low = self.get(End::Hook, 0, m, c - 3)?;
+            let far = self.get(End::Top, 0, n + 2, c - 2)?;
+
+            let mut segs = vec![self.all(low)];
+            segs.extend(middle);
+            segs.push(self.all(far).skip(1).head(c - 2));
+            segs.push(one(&top(m, c - 1)));
+            return Some(segs);
+        }
+
+        if m != 2 * (c - 3) {
+            return None;
+        }
+
+        let low = self.get(End::Hook, 2, m, c - 3)?;
+        let far = self.get(End::Top, 0, n + 2, n + 2)?;
+
+        let mut segs = vec![self.all(low)];
+        segs.extend(middle);
+        segs.push(self.all(far).skip(1).trim(1).head(c - 2));
+        segs.push(one(&second(m, c - 3)));
+        segs.push(one(&top(m, c - 3)));
+        segs.push(one(&[(c - 2, 1), (n + 2, 1)]));
+        segs.push(one(&top(m, c - 1)));
+        Some(segs)
+    }
+
+    /// Makes the partitions that end below `c`, without the top, when
+    /// `c` is about half of `m`.
+    fn below_short(&mut self, m: usize, c: usize) -> Option<Vec<Seg>> {
+        let n = m - c;
+        let rest = self.get(End::Top, 0, n, n)?;
+
+        if m == 2 * c - 1 {
+            let low = self.get(End::Hook, 1, m, c - 1)?;
+            return Some(vec![
+                self.all(low),
+                self.all(rest).trim(1).head(c),
+                one(&top(m, c - 1)),
+            ]);
+        }
+
+        if m != 2 * c - 2 {
+            return None;
+        }
+
+        let low = self.get(End::Hook, 2, m, c - 1)?;
+        Some(vec![
+            self.all(low),
+            self.all(rest).trim(1).head(c),
+            one(&second(m, c - 1)),
+            one(&top(m, c - 1)),
+        ])
+    }
+
+    fn pairs(&mut self, cut: u8, m: usize) -> Option<Vec<Seg>> {
+        let rest = self.get(End::Below, cut, m - 3, 3)?;
+        let twos = self.get(End::Top, 0, m, 2)?;
+        Some(vec![
+            one(&[(1, m)]),
+            one(&[(2, 1), (1, m - 2)]),
+            self.all(rest).head(3),
+            self.all(twos).skip(2).rev(),
+        ])
+    }
+
+    /// Finds where a partition is in a piece.
+    fn find(&self, order: usize, x: &[usize]) -> Option<u128> {
+        // The pieces being looked in, outermost first, with how many
+        // parts of `x` their heads cover, and the stretch being looked
+        // at and where it starts. When a piece has been looked in, what
+        // was found is passed back to the one it's a stretch of.
+        let mut stack = Vec::new();
+        let mut found = self.look(order, x, 0, &mut stack);
+        loop {
+            let (order, skip, seg, start) = match stack.last() {
+                Some(&top) => top,
+                None => return found.and_then(|i| i),
+            };
+
+            let segs = match self.orders[order] {
+                Order::Plan { ref segs, .. } => segs,
+                Order::Twos(_) => unreachable!(),
+            };
+
+            let i = match found.take() {
+                Some(i) => match segs[seg] {
+                    Seg::Sub { at, len, rev, .. } => i
+                        .filter(|&i| at <= i && i < at + len)
+                        .map(|i| start + if rev { at + len - 1 - i } else { i - at }),
+                    Seg::One(_) => unreachable!(),
+                },
+                None if seg == segs.len() => {
+                    stack.pop();
+                    found = Some(None);
+                    continue;
+                },
+                None => match segs[seg] {
+                    Seg::One(ref runs) => {
+                        let y = runs.iter().flat_map(|&(y, c)| iter::repeat_n(y, c));
+                        if y.eq(x[skip..].iter().cloned()) {
+                            Some(start)
+                        } else {
+                            None
+                        }
+                    },
+                    Seg::Sub { order, head, .. } => {
+                        let y = &x[skip..];
+                        if y.len() >= head.1 && y[..head.1].iter().all(|&z| z == head.0) {
+                            found = self.look(order, x, skip + head.1, &mut stack);
+                            continue;
+                        }
+                        None
+                    },
+                },
+            };
+
+            if i.is_some() {
+                stack.pop();
+                found = Some(i);
+            } else if let Some(top) = stack.last_mut() {
+                top.2 += 1;
+                top.3 += segs[seg].len();
+            }
+        }
+    }
+
+    /// Starts looking for `x[skip..]` in a piece, unless it's already
+    /// known whether it's there.
+    fn look(
+        &self,
+        order: usize,
+        x: &[usize],
+        skip: usize,
+        stack: &mut Vec<(usize, usize, usize, u128)>,
+    ) -> Option<Option<u128>> {
+        let y = &x[skip..];
+        match self.orders[order] {
+            Order::Plan { k, .. } if y.first().is_some_and(|&z| z > k) => Some(None),
+            Order::Plan { .. } => {
+                stack.push((order, skip, 0, 0));
+                None
+            },
+            Order::Twos(_) if y.iter().all(|&z| z <= 2) => {
+                Some(Some(y.iter().filter(|&&z| z == 2).count() as u128))
+            },
+            Order::Twos(_) => Some(None),
+        }
+    }
+
+    /// Finds the biggest part of the `i`-th partition in a piece.
+    fn first(&self, mut order: usize, mut i: u128) -> usize {
+        loop {
+            let segs = match self.orders[order] {
+                Order::Plan { ref segs, .. } => segs,
+                Order::Twos(m) => return if i > 0 { 2 } else { m.min(1) },
+            };
+
+            let mut segs = segs.iter();
+            let seg = loop {
+                let seg = segs.next().unwrap();
+                if i < seg.len() {
+                    break seg;
+                }
+                i -= seg.len();
+            };
+
+            match *seg {
+                Seg::One(ref runs) => return runs.first().map_or(0, |r| r.0),
+                Seg::Sub { head, .. } if head.1 > 0 => return head.0,
+                Seg::Sub { order: inner, at, len, rev, .. } => {
+                    order = inner;
+                    i = if rev { at + len - 1 - i } else { at + i };
+                },
+            }
+        }
+    }
+}
+
+/// Turns a kind of piece into the one that's planned for it, or
+/// returns nothing if there isn't one.
+fn normalize(end: End, cut: u8, m: usize, k: usize) -> Option<(End, u8, usize, usize)> {
+    let top = |k: usize| Some((End::Top, 0, m, if m == 0 { 0 } else { k.clamp(1, m) }));
+
+    match (end, cut) {
+        (End::Top, _) => top(k),
+        (End::Hook, 0) => {
+            let c = k.min(m);
+            if c == m || m <= 2 {
+                top(m)
+            } else if c <= 2 || m.is_multiple_of(c) {
+                None
+            } else {
+                Some((end, cut, m, c))
+            }
+        },
+        (End::Hook, 1) => {
+            let c = k.min(m);
+            if c < 3 || m < c + 2 {
+                None
+            } else {
+                Some((end, cut, m, c))
+            }
+        },
+        (End::Hook, _) => {
+            if k < 3 || !m.is_multiple_of(k) || m < 2 * k {
+                None
+            } else {
+                Some((end, cut, m, k))
+            }
+        },
+        (End::Below, 0) => {
+            if k > m {
+                top(m)
+            } else if k <= 2 || m.is_multiple_of(k) {
+                None
+            } else {
+                Some((end, cut, m, k))
+            }
+        },
+        (End::Below, 1) => {
+            if k < 3 || m < k {
+                None
+            } else if m == k {
+                top(m - 1)
+            } else if m == k + 1 {
+                top(m - 2)
+            } else {
+                Some((end, cut, m, k))
+            }
+        },
+        (End::Below, _) => {
+            if k < 3 || m == 0 || !m.is_multiple_of(k) {
+                None
+            } else if m == k {
+                normalize(End::Below, 1, m, k)
+            } else {
+                Some((end, cut, m, k))
+            }
+        },
+        (End::Pairs, 0) if m < 4 || m.is_multiple_of(3) => None,
+        (End::Pairs, 1) if m < 4 => None,
+        (End::Pairs, 2) if m < 6 || !m.is_multiple_of(3) => None,
+        (End::Pairs, _) => Some((end, cut, m, 3)),
+    }
+}
+
+fn plan(segs: Vec<Seg>, k: usize) -> Order {
+    let len = segs.iter().fold(0u128, |len, seg| len.saturating_add(seg.len()));
+    Order::Plan { segs, len, k }
+}
+
+fn one(runs: &[(usize, usize)]) -> Seg {
+    Seg::One(runs.iter().cloned().filter(|&(x, c)| x > 0 && c > 0).collect())
+}
+
+fn expand(runs: &[(usize, usize)], out: &mut Vec<usize>) {
+    for &(x, c) in runs.iter().filter(|r| r.0 > 0) {
+        out.extend(iter::repeat_n(x, c));
+    }
+}
+
+/// The top, `k, k, ..., k, r`.
+fn top(m: usize, k: usize) -> [(usize, usize); 2] {
+    match k.min(m) {
+        0 => [(0, 0), (0, 0)],
+        k => [(k, m / k), (m % k, 1)],
+    }
+}
+
+/// The partition after the top when `k` divides `m`, which is the top
+/// with its last `k` split into `k - 1` and one.
+fn second(m: usize, k: usize) -> [(usize, usize); 3] {
+    [(k, m / k - 1), (k - 1, 1), (1, 1)]
+}
+
+fn hook(m: usize, k: usize) -> [(usize, usize); 2] {
+    [(k, 1), (1, m - k)]
+}
+
+/// Lists the partitions in a piece, in reverse lexicographic order, or
+/// returns nothing if there are more than `LARGE` of them.
+fn vertices(cut: u8, m: usize, k: usize) -> Option<Vec<Vec<usize>>> {
+    // There are at least `(m + 3)^2 / 12` partitions with no part
+    // bigger than three, which rules out most pieces straight away.
+    let cut = cut as usize;
+    if k >= 3 && (m + 3) * (m + 3) / 12 > LARGE + cut {
+        return None;
+    }
+
+    let lex = || {
+        let mut lex = Lexicographic {
+            a: Vec::new(),
+            n: m,
+            started: true,
+        };
+        expand(&top(m, k), &mut lex.a);
+        lex
+    };
+
+    // The top comes first, and then the partition after it, so the ones
+    // that are left out are at the front.
+    let mut more = lex();
+    for _ in 0..(LARGE + cut) {
+        if more.next().is_none() {
+            let mut more = lex();
+            let mut out = vec![more.a.clone()];
+            while let Some(x) = more.next() {
+                out.push(x.to_vec());
+            }
+
+            out.drain(..cut.min(out.len()));
+            return Some(out);
+        }
+    }
+
+    None
+}
+
+/// Searches for a way through all the partitions of a piece, from all
+/// ones to where the piece ends.
+///
+/// This is a depth-first search that tries the partitions with the
+/// fewest ways on first, and gives up on a branch as soon as the rest
+/// of the partitions are cut off, or one of them can't be reached.
+fn search(all: &[Vec<usize>], end: End, m: usize, k: usize) -> Option<Vec<Seg>> {
+    let mut x = Vec::new();
+    match end {
+        End::Top => expand(&top(m, k), &mut x),
+        End::Hook => expand(&hook(m, k), &mut x),
+        End::Below => expand(&top(m, k - 1), &mut x),
+        End::Pairs => expand(&[(2, 2), (1, m - 4)], &mut x),
+    }
+
+    let s = all.iter().position(|y| y.iter().all(|&z| z == 1))?;
+    let e = all.iter().position(|y| *y == x)?;
+    let n = all.len();
+
+    let adj: Vec<Vec<usize>> = all.iter()
+        .map(|y| (0..n).filter(|&j| adjacent(y, &all[j])).collect())
+        .collect();
+    let mut search = Search {
+        deg: adj.iter().map(Vec::len).collect(),
+        adj,
+        seen: vec![false; n],
+        path: vec![s],
+        end: e,
+    };
+
+    search.seen[s] = true;
+    for &w in &search.adj[s] {
+        search.deg[w] -= 1;
+    }
+
+    if (s == e && n > 1) || (s != e && !search.go(s)) {
+        return None;
+    }
+
+    Some(search.path.iter().map(|&i| runs(&all[i])).collect())
+}
+
+struct Search {
+    adj: Vec<Vec<usize>>,
+    /// How many of each partition's neighbours haven't been visited.
+    deg: Vec<usize>,
+    seen: Vec<bool>,
+    path: Vec<usize>,
+    end: usize,
+}
+
+impl Search {
+    fn go(&mut self, v: usize) -> bool {
+        let n = self.adj.len();
+        if self.path.len() == n {
+            return v == self.end;
+        }
+
+        // A neighbour with only one way on has to be visited now, since
+        // it can't be the end.
+        let mut next: Vec<usize> = self.adj[v].iter()
+            .cloned()
+            .filter(|&w| !self.seen[w] && w != self.end && self.deg[w] == 1)
+            .collect();
+
+        if next.len() > 1 {
+            return false;
+        } else if next.is_empty() {
+            next = self.adj[v].iter()
+                .cloned()
+                .filter(|&w| !self.seen[w] && (w != self.end || self.path.len() + 1 == n))
+                .collect();
+        }
+
+        next.sort_by_key(|&w| self.deg[w]);
+        for w in next {
+            self.seen[w] = true;
+            self.path.push(w);
+            for &x in &self.adj[w] {
+                self.deg[x] -= 1;
+            }
+
+            if self.connected(w) && self.go(w) {
+                return true;
+            }
+
+            for &x in &self.adj[w] {
+                self.deg[x] += 1;
+            }
+            self.path.pop();
+            self.seen[w] = false;
+        }
+
+        false
+    }
+
+    /// Checks that the partitions that are left can all be reached from
+    /// `v`, and that they all still have a way in.
+    fn connected(&self, v: usize) -> bool {
+        let n = self.adj.len();
+        let mut found = vec![false; n];
+        let mut stack = vec![v];
+        let mut count = 0;
+
+        found[v] = true;
+        while let Some(x) = stack.pop() {
+            for &w in &self.adj[x] {
+                if !self.seen[w] && !found[w] {
+                    found[w] = true;
+                    stack.push(w);
+                    count += 1;
+                }
+            }
+        }
+
+        count == n - self.path.len()
+            && (0..n).all(|w| self.seen[w] || w == self.end || self.deg[w] > 0)
+    }
+}
+
+/// Checks whether one unit moves between two partitions.
+fn adjacent(x: &[usize], y: &[usize]) -> bool {
+    let part = |z: &[usize], i: usize| z.get(i).cloned().unwrap_or(0);
+    let len = x.len().max(y.len());
+    (0..len).map(|i| part(x, i).abs_diff(part(y, i))).sum::<usize>() == 2
+}
+
+/// Finds the move that turns one partition into the next.
+///
+/// Their parts, in order, are the same except for one that's a unit
+/// smaller and one that's a unit bigger, which are where the unit was
+/// moved from and to.
+fn shift(x: &[usize], y: &[usize]) -> Move {
+    let part = |z: &[usize], i: usize| z.get(i).cloned().unwrap_or(0);
+    let mut m = Move { from: 0, to: 0 };
+    for i in 0..x.len().max(y.len()) {
+        let (p, q) = (part(x, i), part(y, i));
+        if q < p {
+            m.from = p;
+        } else if q > p {
+            m.to = p;
+        }
+    }
+
+    m
+}
+
+fn runs(x: &[usize]) -> Seg {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for &y in x {
+        match runs.last_mut() {
+            Some(r) if r.0 == y => r.1 += 1,
+            _ => runs.push((y, 1)),
+        }
+    }
+
+    Seg::One(runs)
+}
+
+#[test]
+fn lexicographic() {
+    //! Checks that the order is right, and that nothing is missed.
+
+    let all = collect!(Lexicographic::new(4));
+    assert_eq!(all, [&[4][..], &[3, 1], &[2, 2], &[2, 1, 1], &[1, 1, 1, 1]]);
+
+    for n in 0..25 {
+        let mut expected = Vec::new();
+        ::Partitions::new(n).for_each(|x| {
+            let mut x = x.to_vec();
+            x.reverse();
+            expected.push(x);
+        });
+
+        let mut p = Lexicographic::new(n);
+        let mut all: Vec<Vec<usize>> = Vec::new();
+        while let Some(x) = p.next() {
+            assert!(x.windows(2).all(|w| w[0] >= w[1]));
+            if let Some(y) = all.last() {
+                assert!(x < &y[..]);
+            }
+            all.push(x.to_vec());
+        }
+
+        all.sort();
+        expected.sort();
+        assert_eq!(all, expected);
+    }
+}
+
+#[test]
+fn gray() {
+    //! Checks that consecutive partitions differ by one unit, that the
+    //! move leads from one to the next, and that nothing is missed.
+
+    for n in 0..40 {
+        let mut expected = Vec::new();
+        ::Partitions::new(n).for_each(|x| {
+            let mut x = x.to_vec();
+            x.reverse();
+            expected.push(x);
+        });
+
+        let mut p = Gray::new(n);
+        let mut all: Vec<Vec<usize>> = Vec::new();
+        while let Some(x) = p.next() {
+            let x = x.to_vec();
+            assert!(x.windows(2).all(|w| w[0] >= w[1]));
+
+            if let Some(y) = all.last() {
+                // Moving a unit changes the parts, in order, by two.
+                let len = x.len().max(y.len());
+                let part = |z: &[usize], i: usize| z.get(i).cloned().unwrap_or(0);
+                let diff: usize = (0..len)
+                    .map(|i| (part(&x, i) as isize - part(y, i) as isize).unsigned_abs())
+                    .sum();
+                assert_eq!(diff, 2);
+
+                let m = p.moves();
+                assert_eq!(m.len(), 1);
+
+                let mut z = y.clone();
+                let i = z.iter().position(|&w| w == m[0].from).unwrap();
+                z[i] -= 1;
+                if m[0].to == 0 {
+                    z.push(1);
+                } else {
+                    let j = z.iter().position(|&w| w == m[0].to).unwrap();
+                    z[j] += 1;
+                }
+                z.retain(|&w| w > 0);
+                z.sort_by(|a, b| b.cmp(a));
+                assert_eq!(z, x);
+            } else {
+                assert!(p.moves().is_empty());
+                assert!(x.iter().all(|&w| w == 1));
+            }
+
+            all.push(x);
+        }
+
+        if n > 0 {
+            assert_eq!(all.last().unwrap(), &[n]);
+        }
+
+        all.sort();
+        expected.sort();
+        assert_eq!(all, expected);
+    }
+}
+
+#[test]
+fn gray_large() {
+    //! Checks that a big order can be planned, that it has room for
+    //! every partition, and that it starts out one unit at a time.
+
+    let n = 600;
+    let mut p = Gray::new(n);
+    assert_eq!(p.orders.last().unwrap().len(), ::count::count(n));
+
+    let mut y = p.next().unwrap().to_vec();
+    assert_eq!(y, vec![1; n]);
+    for _ in 0..10000 {
+        let x = p.next().unwrap().to_vec();
+        assert!(x.windows(2).all(|w| w[0] >= w[1]));
+        assert_eq!(x.iter().sum::<usize>(), n);
+        assert!(adjacent(&x, &y));
+        y = x;
+    }
+}
+
+#[test]
+fn colex() {
+    //! Checks that the order is colexicographic, and that nothing is
+    //! missed.
+
+    let colex = |x: &[usize], y: &[usize]| x.iter().rev().cmp(y.iter().rev());
+
+    for n in 0..25 {
+        let mut p = ::Partitions::colex(n);
+        let mut all: Vec<Vec<usize>> = Vec::new();
+        while let Some(x) = p.next() {
+            assert!(x.windows(2).all(|w| w[0] >= w[1]));
+            if let Some(y) = all.last() {
+                assert_eq!(colex(y, x), core::cmp::Ordering::Less);
+            }
+            all.push(x.to_vec());
+        }
+
+        let mut expected: Vec<Vec<usize>> = ::Partitions::new(n).into_iter().collect();
+        for x in &mut all {
+            x.reverse();
+        }
+        all.sort();
+        expected.sort();
+        assert_eq!(all, expected);
+    }
+
+    let all = collect!(::Partitions::colex(4));
+    assert_eq!(all, [&[1, 1, 1, 1][..], &[2, 1, 1], &[3, 1], &[2, 2], &[4]]);
+}
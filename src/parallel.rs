@@ -0,0 +1,47 @@
+//! Enumerating partitions in parallel.
+
+use alloc::vec::Vec;
+use core::iter;
+use rayon::prelude::*;
+
+use Restricted;
+
+/// Makes a parallel iterator over the partitions of `n`.
+///
+/// The work is split up by the largest part, since the partitions
+/// with a given largest part `m` are just those of `n - m` with parts
+/// no greater than `m`, which can be enumerated independently. The
+/// order that the partitions come out in is unspecified, but the
+/// parts of each one are in ascending order.
+pub fn par_partitions(n: usize) -> impl ParallelIterator<Item = Vec<usize>> {
+    // The empty partition has no largest part, so it gets m = 0.
+    let ms = if n == 0 { 0..1 } else { 1..(n + 1) };
+
+    ms.into_par_iter().flat_map_iter(move |m| {
+        let mut p = Restricted::with_max_part(n - m, m);
+
+        iter::from_fn(move || {
+            p.next().map(|x| {
+                let mut x = x.to_vec();
+                if m > 0 {
+                    x.push(m);
+                }
+                x
+            })
+        })
+    })
+}
+
+#[test]
+fn sequential() {
+    //! Checks that the same partitions come out as sequentially.
+
+    for n in 0..26 {
+        let mut expected: Vec<_> = ::Partitions::new(n).into_iter().collect();
+        let mut actual: Vec<_> = par_partitions(n).collect();
+
+        expected.sort();
+        actual.sort();
+        assert_eq!(actual, expected);
+    }
+}
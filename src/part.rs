@@ -0,0 +1,32 @@
+//! The types that parts can be stored as.
+
+/// An unsigned integer type that parts can be stored as.
+///
+/// All of the arithmetic is done with `usize`, so this only needs to
+/// convert to and from it.
+pub trait Part: Copy {
+    /// Converts from a `usize`, which is never greater than the
+    /// number being partitioned.
+    fn from_usize(x: usize) -> Self;
+
+    /// Converts back into a `usize`.
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! impl_part {
+    ($($t:ty)*) => {$(
+        impl Part for $t {
+            #[inline]
+            fn from_usize(x: usize) -> $t {
+                x as $t
+            }
+
+            #[inline]
+            fn to_usize(self) -> usize {
+                self as usize
+            }
+        }
+    )*};
+}
+
+impl_part!(u8 u16 u32 u64 usize);
@@ -0,0 +1,271 @@
+//! A checked wrapper around the parts of a partition.
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::iter::FusedIterator;
+
+use IntoIter;
+
+/// A partition, with its parts in ascending order.
+///
+/// Partitions are ordered by dominance: one partition is at least
+/// another if it has the same sum, and the sum of its `i` largest parts
+/// is at least as big as the other's for every `i`. Partitions with
+/// different sums, or whose partial sums cross, are incomparable.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Partition(Vec<usize>);
+
+impl Partition {
+    /// Wraps a list of parts, or returns `None` if they aren't all
+    /// positive and in ascending order.
+    pub fn try_new(parts: Vec<usize>) -> Option<Partition> {
+        if parts.first() == Some(&0) || parts.windows(2).any(|w| w[0] > w[1]) {
+            None
+        } else {
+            Some(Partition(parts))
+        }
+    }
+
+    /// Returns the parts, in ascending order.
+    #[inline]
+    pub fn parts(&self) -> &[usize] {
+        &self.0
+    }
+
+    /// Returns the sum of the parts.
+    #[inline]
+    pub fn sum(&self) -> usize {
+        self.0.iter().sum()
+    }
+
+    /// Unwraps the parts.
+    #[inline]
+    pub fn into_vec(self) -> Vec<usize> {
+        self.0
+    }
+}
+
+impl PartialOrd for Partition {
+    fn partial_cmp(&self, other: &Partition) -> Option<Ordering> {
+        if self.sum() != other.sum() {
+            return None;
+        }
+
+        let mut a = self.0.iter().rev();
+        let mut b = other.0.iter().rev();
+        let (mut x, mut y) = (0, 0);
+        let (mut less, mut greater) = (false, false);
+
+        loop {
+            match (a.next(), b.next()) {
+                (None, None) => break,
+                (p, q) => {
+                    x += p.cloned().unwrap_or(0);
+                    y += q.cloned().unwrap_or(0);
+                },
+            }
+
+            less |= x < y;
+            greater |= x > y;
+        }
+
+        match (less, greater) {
+            (false, false) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (true, true) => None,
+        }
+    }
+}
+
+/// Checks whether `parts` is a partition of `n` in the form that the
+/// iterators yield, with positive parts in ascending order.
+pub fn is_partition_of(n: usize, parts: &[usize]) -> bool {
+    if parts.first() == Some(&0) || parts.windows(2).any(|w| w[0] > w[1]) {
+        return false;
+    }
+
+    // The parts could add up to more than a `usize` can hold.
+    parts.iter().try_fold(0usize, |sum, &x| sum.checked_add(x)) == Some(n)
+}
+
+/// Finds the sum of two partitions, which has the parts of both.
+///
+/// The parts can be in any order, and the result has them in
+/// descending order, without any zeros.
+pub fn partition_sum(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut out: Vec<usize> = a.iter().chain(b).cloned().filter(|&x| x > 0).collect();
+    out.sort_by(|x, y| y.cmp(x));
+    out
+}
+
+/// Finds the union of two partitions, which has each part as many
+/// times as whichever of them has it more often.
+///
+/// The parts can be in any order, and the result has them in
+/// descending order, without any zeros.
+pub fn partition_union(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let a = partition_sum(a, &[]);
+    let b = partition_sum(b, &[]);
+    let mut out = Vec::with_capacity(a.len().max(b.len()));
+
+    // Each part that's in both is only taken once.
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Greater => {
+                out.push(a[i]);
+                i += 1;
+            },
+            Ordering::Less => {
+                out.push(b[j]);
+                j += 1;
+            },
+            Ordering::Equal => {
+                out.push(a[i]);
+                i += 1;
+                j += 1;
+            },
+        }
+    }
+
+    out.extend_from_slice(&a[i..]);
+    out.extend_from_slice(&b[j..]);
+    out
+}
+
+/// Iterates over partitions as `Partition`s.
+///
+/// This is made by `Partitions::typed`.
+pub struct Typed {
+    inner: IntoIter,
+}
+
+impl Typed {
+    #[inline]
+    pub(crate) fn new(inner: IntoIter) -> Typed {
+        Typed { inner }
+    }
+}
+
+impl Iterator for Typed {
+    type Item = Partition;
+
+    #[inline]
+    fn next(&mut self) -> Option<Partition> {
+        self.inner.next().map(Partition)
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Partition> {
+        self.inner.nth(n).map(Partition)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Typed {
+    #[inline]
+    fn next_back(&mut self) -> Option<Partition> {
+        self.inner.next_back().map(Partition)
+    }
+}
+
+impl FusedIterator for Typed {}
+
+#[test]
+fn try_new() {
+    //! Checks which lists of parts are accepted.
+
+    assert!(Partition::try_new(vec![]).is_some());
+    assert!(Partition::try_new(vec![1, 1, 3]).is_some());
+    assert!(Partition::try_new(vec![0, 1]).is_none());
+    assert!(Partition::try_new(vec![3, 1]).is_none());
+}
+
+#[test]
+fn is_partition() {
+    //! Checks some valid and invalid partitions.
+
+    assert!(is_partition_of(0, &[]));
+    assert!(is_partition_of(4, &[1, 1, 2]));
+    assert!(is_partition_of(4, &[4]));
+
+    assert!(!is_partition_of(1, &[]));
+    assert!(!is_partition_of(0, &[0]));
+    assert!(!is_partition_of(3, &[0, 3]));
+    assert!(!is_partition_of(4, &[1, 2]));
+    assert!(!is_partition_of(4, &[2, 1, 1]));
+    assert!(!is_partition_of(0, &[usize::MAX, usize::MAX, usize::MAX]));
+    assert!(!is_partition_of(1, &[1, usize::MAX]));
+
+    for n in 0..15 {
+        ::Partitions::new(n).for_each(|x| {
+            assert!(is_partition_of(n, x));
+            assert!(!is_partition_of(n + 1, x));
+        });
+    }
+}
+
+#[test]
+fn arithmetic() {
+    //! Checks some sums and unions, and that the union is at least as
+    //! big as either one and no bigger than the sum.
+
+    assert_eq!(partition_sum(&[2, 1], &[3]), [3, 2, 1]);
+    assert_eq!(partition_sum(&[], &[]), Vec::<usize>::new());
+    assert_eq!(partition_sum(&[1, 0, 4], &[]), [4, 1]);
+
+    assert_eq!(partition_union(&[2, 2, 1], &[2, 1, 1]), [2, 2, 1, 1]);
+    assert_eq!(partition_union(&[], &[3, 1]), [3, 1]);
+    assert_eq!(partition_union(&[1, 3, 3], &[3, 2]), [3, 3, 2, 1]);
+
+    let mut all = Vec::new();
+    for n in 0..7 {
+        ::Partitions::new(n).for_each(|x| all.push(x.to_vec()));
+    }
+
+    for a in &all {
+        for b in &all {
+            let sum = partition_sum(a, b);
+            let union = partition_union(a, b);
+            assert_eq!(union, partition_union(b, a));
+            let total = a.iter().sum::<usize>() + b.iter().sum::<usize>();
+            assert_eq!(sum.iter().sum::<usize>(), total);
+
+            for x in 1..7 {
+                let count = |p: &[usize]| p.iter().filter(|&&y| y == x).count();
+                assert_eq!(count(&union), count(a).max(count(b)));
+                assert_eq!(count(&sum), count(a) + count(b));
+            }
+        }
+    }
+}
+
+#[test]
+fn dominance() {
+    //! Checks some comparable and incomparable pairs.
+
+    let p = |x: &[usize]| Partition::try_new(x.to_vec()).unwrap();
+
+    assert!(p(&[1, 3]) > p(&[2, 2]));
+    assert!(p(&[2, 2]) < p(&[1, 3]));
+    assert!(p(&[1, 1, 1, 1]) < p(&[4]));
+    assert_eq!(p(&[1, 3]).partial_cmp(&p(&[1, 3])), Some(Ordering::Equal));
+
+    // The partial sums go 3, 4, 5 against 2, 4, 6.
+    assert_eq!(p(&[1, 1, 1, 3]).partial_cmp(&p(&[2, 2, 2])), None);
+    assert_eq!(p(&[1, 3]).partial_cmp(&p(&[1, 2])), None);
+}
+
+#[test]
+fn typed() {
+    //! Checks that the adapter yields the usual partitions.
+
+    let expected: Vec<Vec<usize>> = ::Partitions::new(10).into_iter().collect();
+    let actual: Vec<Vec<usize>> = ::Partitions::new(10).typed().map(Partition::into_vec).collect();
+    assert_eq!(actual, expected);
+}
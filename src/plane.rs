@@ -0,0 +1,162 @@
+//! Plane partitions.
+
+use alloc::vec::Vec;
+
+/// Iterates over the plane partitions of a given integer.
+///
+/// A plane partition is a grid of positive integers that never
+/// increase along a row or down a column, so each plane partition is
+/// yielded as its rows, from the top, each with its parts in descending
+/// order. There are A000219(n) of them.
+///
+/// Each row is a partition that fits under the row above it, and the
+/// rows are chosen one at a time, each going through every row that
+/// fits in lexicographic order.
+pub struct PlanePartitions {
+    /// The rows, padded with zeros to the length of the row above.
+    rows: Vec<Vec<usize>>,
+    /// What was left for each row and the ones below it.
+    rem: Vec<usize>,
+    n: usize,
+    state: State,
+}
+
+enum State {
+    Start,
+    Running,
+    Done,
+}
+
+impl PlanePartitions {
+    /// Makes an iterator over the plane partitions of `n`.
+    pub fn new(n: usize) -> PlanePartitions {
+        PlanePartitions {
+            rows: Vec::new(),
+            rem: Vec::new(),
+            n,
+            state: State::Start,
+        }
+    }
+
+    /// Fills in the rest of the rows with the first ones that fit,
+    /// which have a single one in each.
+    fn fill(&mut self, mut r: usize) {
+        while r > 0 {
+            let len = match self.rows.last() {
+                Some(row) => row.iter().take_while(|&&x| x > 0).count(),
+                None => r,
+            };
+
+            let mut row = vec![0; len];
+            row[0] = 1;
+
+            self.rows.push(row);
+            self.rem.push(r);
+            r -= 1;
+        }
+    }
+
+    /// Replaces the last row with the next one that fits, returning
+    /// false if there aren't any more.
+    fn bump(&mut self) -> bool {
+        let i = self.rows.len() - 1;
+        let r = self.rem[i];
+        let (above, row) = self.rows.split_at_mut(i);
+        let row = &mut row[0];
+
+        // The last part that can grow does, and everything after it
+        // goes back to zero.
+        let mut sum: usize = row.iter().sum();
+        for j in (0..row.len()).rev() {
+            let limit = match above.last() {
+                Some(above) => above[j],
+                None => r,
+            };
+            let left = if j == 0 { limit } else { row[j - 1].min(limit) };
+
+            if row[j] < left && sum < r {
+                row[j] += 1;
+                return true;
+            }
+
+            sum -= row[j];
+            row[j] = 0;
+        }
+
+        false
+    }
+}
+
+impl Iterator for PlanePartitions {
+    type Item = Vec<Vec<usize>>;
+
+    fn next(&mut self) -> Option<Vec<Vec<usize>>> {
+        match self.state {
+            State::Start => {
+                self.state = State::Running;
+                let n = self.n;
+                self.fill(n);
+            },
+            State::Running => loop {
+                if self.rows.is_empty() {
+                    self.state = State::Done;
+                    return None;
+                }
+
+                if self.bump() {
+                    let i = self.rows.len() - 1;
+                    let r = self.rem[i] - self.rows[i].iter().sum::<usize>();
+                    self.fill(r);
+                    break;
+                }
+
+                self.rows.pop();
+                self.rem.pop();
+            },
+            State::Done => return None,
+        }
+
+        if self.rows.is_empty() {
+            self.state = State::Done;
+        }
+
+        Some(
+            self.rows
+                .iter()
+                .map(|row| row.iter().cloned().take_while(|&x| x > 0).collect())
+                .collect(),
+        )
+    }
+}
+
+#[test]
+fn oeis() {
+    //! Tests the first few entries of A000219, and checks the shapes.
+
+    let tests: &[usize] = &[1, 1, 3, 6, 13, 24, 48, 86, 160, 282, 500, 859];
+
+    for (n, &c) in tests.iter().enumerate() {
+        let mut all = Vec::new();
+
+        for x in PlanePartitions::new(n) {
+            let sum: usize = x.iter().map(|row| row.iter().sum::<usize>()).sum();
+            assert_eq!(sum, n);
+
+            for (i, row) in x.iter().enumerate() {
+                assert!(!row.is_empty() && row.iter().all(|&y| y > 0));
+                assert!(row.windows(2).all(|w| w[0] >= w[1]));
+                if i > 0 {
+                    assert!(row.len() <= x[i - 1].len());
+                    assert!(row.iter().zip(&x[i - 1]).all(|(a, b)| a <= b));
+                }
+            }
+
+            all.push(x);
+        }
+
+        assert_eq!(all.len(), c);
+        all.sort();
+        all.dedup();
+        assert_eq!(all.len(), c);
+    }
+}
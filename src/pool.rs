@@ -0,0 +1,161 @@
+//! Sharing buffers between many iterators.
+
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::ops::{Deref, DerefMut};
+
+use Partitions;
+
+/// A pool of buffers for making lots of iterators without allocating
+/// for each one.
+///
+/// Each iterator that the pool hands out gives its buffer back when
+/// it's dropped, and every buffer is grown to fit the largest number
+/// that's been asked for, so a pool that's been used for the biggest
+/// `n` once never needs to allocate again.
+#[derive(Default)]
+pub struct PartitionPool {
+    free: RefCell<Vec<Vec<usize>>>,
+    max: Cell<usize>,
+}
+
+impl PartitionPool {
+    /// Makes an empty pool.
+    #[inline]
+    pub fn new() -> PartitionPool {
+        PartitionPool::default()
+    }
+
+    /// Makes an iterator over the partitions of `n`, using a buffer
+    /// from the pool if there's one free.
+    pub fn get(&self, n: usize) -> Pooled<'_> {
+        let max = self.max.get().max(n);
+        self.max.set(max);
+
+        let mut vec = self.free.borrow_mut().pop().unwrap_or_default();
+        vec.clear();
+        vec.reserve(max + 1);
+
+        Pooled {
+            pool: self,
+            inner: Some(Partitions::recycle(n, vec)),
+        }
+    }
+
+    /// Returns the number of buffers that aren't in use.
+    #[inline]
+    pub fn free(&self) -> usize {
+        self.free.borrow().len()
+    }
+}
+
+/// An iterator from a `PartitionPool`, which gives its buffer back to
+/// the pool when it's dropped.
+///
+/// This dereferences to the `Partitions` that it wraps.
+pub struct Pooled<'a> {
+    pool: &'a PartitionPool,
+    inner: Option<Partitions>,
+}
+
+impl<'a> Deref for Pooled<'a> {
+    type Target = Partitions;
+
+    #[inline]
+    fn deref(&self) -> &Partitions {
+        self.inner.as_ref().unwrap()
+    }
+}
+
+impl<'a> DerefMut for Pooled<'a> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Partitions {
+        self.inner.as_mut().unwrap()
+    }
+}
+
+impl<'a> Drop for Pooled<'a> {
+    fn drop(&mut self) {
+        if let Some(p) = self.inner.take() {
+            self.pool.free.borrow_mut().push(p.end());
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod counting {
+    //! An allocator that counts the allocations made on each thread, so
+    //! that the tests running alongside don't get in the way.
+
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    std::thread_local! {
+        static COUNT: Cell<usize> = const { Cell::new(0) };
+    }
+
+    pub struct Counting;
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = COUNT.try_with(|c| c.set(c.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, size: usize) -> *mut u8 {
+            let _ = COUNT.try_with(|c| c.set(c.get() + 1));
+            System.realloc(ptr, layout, size)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    pub fn allocations() -> usize {
+        COUNT.with(|c| c.get())
+    }
+}
+
+#[test]
+fn reuse() {
+    //! Checks that the pooled iterators are right, and that they stop
+    //! allocating once the pool has grown.
+
+    use count;
+
+    let pool = PartitionPool::new();
+    drop(pool.get(40));
+    assert_eq!(pool.free(), 1);
+
+    // Counting allocates, so it has to happen up front.
+    let counts: Vec<u128> = (0..41).map(count).collect();
+
+    let before = counting::allocations();
+    for round in 0..10 {
+        for i in 0..41 {
+            let n = (i + round) % 41;
+            let mut p = pool.get(n);
+
+            let mut c = 0;
+            while let Some(x) = p.next() {
+                assert_eq!(x.iter().sum::<usize>(), n);
+                c += 1;
+            }
+            assert_eq!(c, counts[n]);
+        }
+    }
+    assert_eq!(counting::allocations(), before);
+
+    // Buffers that are in use at the same time need one each.
+    {
+        let _a = pool.get(5);
+        let mut b = pool.get(30);
+        assert_eq!(pool.free(), 0);
+        assert_eq!(b.next(), Some(&[1; 30][..]));
+    }
+    assert_eq!(pool.free(), 2);
+}
@@ -0,0 +1,183 @@
+//! Ranking partitions by their position in the usual order.
+
+use alloc::vec::Vec;
+
+/// The largest `n` whose partitions can be ranked, since there are
+/// more than `u64::MAX` partitions of anything bigger.
+pub const MAX_RANK: usize = 416;
+
+/// A position in the partitions of some number, which can be used to
+/// carry on iterating from there with `Partitions::from_cursor`.
+///
+/// This is just the number of partitions that came before it, so it's
+/// much smaller than the iterator itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cursor {
+    index: u64,
+}
+
+impl Cursor {
+    /// Makes a cursor that's just before the partition with the given
+    /// rank, or equivalently after `index` partitions.
+    #[inline]
+    pub fn at(index: u64) -> Cursor {
+        Cursor { index }
+    }
+
+    /// Returns how many partitions come before the cursor.
+    #[inline]
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+}
+
+/// Splits the positions of the partitions of `n` into `shards` ranges
+/// that are as close to the same size as possible.
+///
+/// Each range is a half-open `(start, end)` pair, to be passed to
+/// `Partitions::range`, and together they cover every partition once.
+/// The positions are `u64`s like a `Cursor`'s, since there can be more
+/// partitions than fit in a `usize`.
+///
+/// # Panics
+///
+/// Panics if `shards` is zero, or if `n > MAX_RANK`.
+pub fn split_ranges(n: usize, shards: usize) -> Vec<(u64, u64)> {
+    assert!(shards != 0, "can't split into zero shards");
+    assert!(n <= MAX_RANK, "can't rank the partitions of {}", n);
+
+    let total = ::count(n);
+    let at = |i: usize| (total * i as u128 / shards as u128) as u64;
+
+    (0..shards).map(|i| (at(i), at(i + 1))).collect()
+}
+
+/// Counts the partitions of each `r <= n` whose parts are all at least
+/// `p`, for every `1 <= p <= n + 1`.
+struct Table {
+    n: usize,
+    q: Vec<u64>,
+}
+
+impl Table {
+    fn new(n: usize) -> Table {
+        assert!(n <= MAX_RANK, "can't rank the partitions of {}", n);
+
+        let w = n + 2;
+        let mut q = vec![0u64; (n + 1) * w];
+
+        for p in (1..w).rev() {
+            q[p] = 1;
+
+            // Either every part is bigger than p, or the first one is p.
+            for r in 1..(n + 1) {
+                let bigger = if p + 1 < w { q[r * w + p + 1] } else { 0 };
+                let first = if p <= r { q[(r - p) * w + p] } else { 0 };
+                q[r * w + p] = bigger + first;
+            }
+        }
+
+        Table { n, q }
+    }
+
+    /// Counts the partitions of `r`.
+    fn all(&self, r: usize) -> u64 {
+        self.q[r * (self.n + 2) + 1]
+    }
+
+    /// Counts the partitions of `r` whose first part is `p`.
+    fn first(&self, r: usize, p: usize) -> u64 {
+        self.q[(r - p) * (self.n + 2) + p]
+    }
+}
+
+/// Finds the position of a partition in the order that `Partitions`
+/// yields them.
+///
+/// The parts must be positive and in ascending order.
+///
+/// # Panics
+///
+/// Panics if the partition is of a number greater than `MAX_RANK`.
+pub fn rank(partition: &[usize]) -> u64 {
+    let n = partition.iter().sum();
+    let table = Table::new(n);
+
+    // Everything that agrees up to some part, but has a smaller part
+    // there, comes first.
+    let mut rank = 0;
+    let mut r = n;
+    let mut low = 1;
+
+    for &x in partition {
+        debug_assert!(x >= low, "the parts must be positive and ascending");
+
+        for v in low..x {
+            rank += table.first(r, v);
+        }
+
+        r -= x;
+        low = x;
+    }
+
+    rank
+}
+
+/// Finds the partition at some position in the order that `Partitions`
+/// yields them, or `None` if there aren't that many.
+///
+/// # Panics
+///
+/// Panics if `n > MAX_RANK`.
+pub fn unrank(n: usize, mut index: u64) -> Option<Vec<usize>> {
+    let table = Table::new(n);
+    if index >= table.all(n) {
+        return None;
+    }
+
+    let mut out = Vec::new();
+    let mut r = n;
+    let mut low = 1;
+
+    while r > 0 {
+        for v in low..(r + 1) {
+            let c = table.first(r, v);
+            if index < c {
+                out.push(v);
+                r -= v;
+                low = v;
+                break;
+            }
+            index -= c;
+        }
+    }
+
+    Some(out)
+}
+
+#[test]
+fn round_trip() {
+    //! Checks that the ranks are the positions in the iterator.
+
+    for n in 0..31 {
+        let mut i = 0;
+        ::Partitions::new(n).for_each(|x| {
+            assert_eq!(rank(x), i);
+            assert_eq!(unrank(n, i).as_ref().map(|y| &y[..]), Some(x));
+            i += 1;
+        });
+
+        assert_eq!(unrank(n, i), None);
+        assert_eq!(unrank(n, u64::MAX), None);
+    }
+}
+
+#[test]
+fn largest() {
+    //! Checks the ends of the largest rankable number.
+
+    let last = ::count(MAX_RANK) as u64 - 1;
+    assert_eq!(unrank(MAX_RANK, 0), Some(vec![1; MAX_RANK]));
+    assert_eq!(unrank(MAX_RANK, last), Some(vec![MAX_RANK]));
+    assert_eq!(rank(&[MAX_RANK]), last);
+}
@@ -0,0 +1,694 @@
+//! Partitions whose parts are restricted in some way.
+
+use alloc::vec::Vec;
+
+/// Iterates over the partitions of a given integer whose parts obey
+/// some restrictions.
+///
+/// The partitions come out in the same order as they would from
+/// `Partitions`, with the parts of each one in ascending order.
+pub struct Restricted {
+    a: Vec<usize>,
+    n: usize,
+    min: usize,
+    max: usize,
+    min_len: usize,
+    max_len: usize,
+    distinct: bool,
+    state: State,
+}
+
+/// Builds up restrictions on partitions.
+///
+/// This is made by `Partitions::builder`, and restrictions can be
+/// added in any order. Applying the same kind twice keeps both, so
+/// `max_part(5).max_part(3)` is the same as `max_part(3)`, and
+/// restrictions that can't all be satisfied just give an empty
+/// iterator.
+pub struct Builder {
+    p: Restricted,
+}
+
+impl Builder {
+    #[inline]
+    pub(crate) fn new(n: usize) -> Builder {
+        Builder { p: Restricted::new(n) }
+    }
+
+    /// Allows no part greater than `m`.
+    #[inline]
+    pub fn max_part(mut self, m: usize) -> Builder {
+        self.p.max = self.p.max.min(m);
+        self
+    }
+
+    /// Allows no part less than `m`.
+    #[inline]
+    pub fn min_part(mut self, m: usize) -> Builder {
+        self.p.min = self.p.min.max(m);
+        self
+    }
+
+    /// Allows only partitions with between `lo` and `hi` parts,
+    /// inclusive.
+    #[inline]
+    pub fn parts_between(mut self, lo: usize, hi: usize) -> Builder {
+        self.p.min_len = self.p.min_len.max(lo);
+        self.p.max_len = self.p.max_len.min(hi);
+        self
+    }
+
+    /// Allows only partitions into distinct parts.
+    #[inline]
+    pub fn distinct(mut self) -> Builder {
+        self.p.distinct = true;
+        self
+    }
+
+    /// Makes the iterator.
+    #[inline]
+    pub fn build(self) -> Restricted {
+        self.p
+    }
+}
+
+enum State {
+    Start,
+    Running,
+    Done,
+}
+
+impl Restricted {
+    /// Makes a new iterator over all of the partitions of `n`, which
+    /// the other constructors then restrict.
+    #[inline]
+    fn new(n: usize) -> Restricted {
+        Restricted {
+            a: Vec::new(),
+            n,
+            min: 1,
+            max: n,
+            min_len: 0,
+            max_len: usize::MAX,
+            distinct: false,
+            state: State::Start,
+        }
+    }
+
+    /// Makes a new iterator over the partitions of `n` with no part
+    /// greater than `m`.
+    #[inline]
+    pub(crate) fn with_max_part(n: usize, m: usize) -> Restricted {
+        let mut p = Restricted::new(n);
+        p.max = m;
+        p
+    }
+
+    /// Makes a new iterator over the partitions of `n` with no part
+    /// less than `m`.
+    #[inline]
+    pub(crate) fn min_part(n: usize, m: usize) -> Restricted {
+        let mut p = Restricted::new(n);
+        p.min = m.max(1);
+        p
+    }
+
+    /// Makes a new iterator over the partitions of `n` into exactly
+    /// `k` parts.
+    #[inline]
+    pub(crate) fn into_parts(n: usize, k: usize) -> Restricted {
+        let mut p = Restricted::new(n);
+        p.min_len = k;
+        p.max_len = k;
+        p
+    }
+
+    /// Makes a new iterator over the partitions of `n` into at most
+    /// `k` parts.
+    #[inline]
+    pub(crate) fn at_most_parts(n: usize, k: usize) -> Restricted {
+        let mut p = Restricted::new(n);
+        p.max_len = k;
+        p
+    }
+
+    /// Makes a new iterator over the partitions of `n` into distinct
+    /// parts.
+    #[inline]
+    pub(crate) fn distinct(n: usize) -> Restricted {
+        let mut p = Restricted::new(n);
+        p.distinct = true;
+        p
+    }
+
+    /// Makes a new iterator over the partitions of `n` into distinct
+    /// parts, none of which is less than `m`.
+    #[inline]
+    pub(crate) fn distinct_min(n: usize, m: usize) -> Restricted {
+        let mut p = Restricted::distinct(n);
+        p.min = m.max(1);
+        p
+    }
+
+    /// Makes a new iterator over the partitions of `n` into exactly
+    /// `k` distinct parts.
+    #[inline]
+    pub(crate) fn distinct_into_parts(n: usize, k: usize) -> Restricted {
+        let mut p = Restricted::into_parts(n, k);
+        p.distinct = true;
+        p
+    }
+
+    /// Makes a new iterator over the partitions of `n` into at most
+    /// `r` parts, none of which is greater than `c`.
+    #[inline]
+    pub(crate) fn boxed(n: usize, r: usize, c: usize) -> Restricted {
+        let mut p = Restricted::new(n);
+        p.max = c;
+        p.max_len = r;
+        p
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        match self.state {
+            State::Start => {
+                let n = self.n;
+                self.a.reserve(n.min(self.max_len));
+                self.state = State::Running;
+
+                let min = self.min;
+                if !self.fill(n, min) {
+                    self.state = State::Done;
+                    return None;
+                }
+            },
+            State::Running => {
+                // The last part can never grow, so it just goes back
+                // into the remainder.
+                let mut r = match self.a.pop() {
+                    Some(x) => x,
+                    None => {
+                        self.state = State::Done;
+                        return None;
+                    },
+                };
+
+                loop {
+                    match self.a.pop() {
+                        Some(x) => {
+                            r += x;
+                            if self.fill(r, x + 1) {
+                                break;
+                            }
+                        },
+                        None => {
+                            self.state = State::Done;
+                            return None;
+                        },
+                    }
+                }
+            },
+            State::Done => return None,
+        }
+
+        Some(&self.a)
+    }
+
+    /// Appends the smallest ascending run of parts, all at least `p`,
+    /// that sums to `r`, or returns false if there isn't one.
+    fn fill(&mut self, mut r: usize, mut p: usize) -> bool {
+        if r == 0 {
+            return self.a.len() >= self.min_len;
+        }
+
+        while r > 0 {
+            match self.smallest(r, p) {
+                Some(x) => {
+                    self.a.push(x);
+                    r -= x;
+                    p = if self.distinct { x + 1 } else { x };
+                },
+                None => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Finds the smallest first part of any partition of `r > 0` into
+    /// parts between `p` and the maximum, that can follow the parts
+    /// already in the buffer.
+    fn smallest(&self, r: usize, p: usize) -> Option<usize> {
+        if p > self.max || p > r {
+            return None;
+        }
+
+        // Using as many parts as possible lets the first be smallest.
+        let len = self.a.len();
+        let c = if self.distinct {
+            distinct_len(r, p).min(self.max - p + 1)
+        } else {
+            r / p
+        }.min(self.max_len - len);
+
+        if c == 0 || self.top(c) < r || len + c < self.min_len {
+            return None;
+        }
+
+        Some(p.max(r.saturating_sub(self.top(c - 1))))
+    }
+
+    /// Finds the largest sum of `c` parts.
+    fn top(&self, c: usize) -> usize {
+        let top = c.saturating_mul(self.max);
+        if self.distinct { top - triangle(c) } else { top }
+    }
+}
+
+/// Iterates over the partitions of a given integer whose largest part
+/// is exactly some size.
+///
+/// This is made by `Partitions::largest_exactly`, and the partitions
+/// come out in the same order as they would from `Partitions`. Each
+/// one is a partition of what's left with no part above the largest,
+/// followed by the largest part.
+pub struct LargestExactly {
+    inner: Option<Restricted>,
+    out: Vec<usize>,
+    m: usize,
+}
+
+impl LargestExactly {
+    #[inline]
+    pub(crate) fn new(n: usize, m: usize) -> LargestExactly {
+        // Only the empty partition has no largest part, so it's counted
+        // as having a largest part of zero.
+        let possible = m <= n && (m == 0) == (n == 0);
+
+        LargestExactly {
+            inner: if possible { Some(Restricted::with_max_part(n - m, m)) } else { None },
+            out: Vec::new(),
+            m,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        let x = self.inner.as_mut()?.next()?;
+
+        self.out.clear();
+        self.out.extend_from_slice(x);
+        if self.m > 0 {
+            self.out.push(self.m);
+        }
+        Some(&self.out)
+    }
+}
+
+/// Groups the partitions of `n` by their number of parts, from the
+/// most parts to the fewest.
+///
+/// Each group is made by its own run of `Partitions::into_parts`, and
+/// the only group for zero is the empty partition, with zero parts.
+pub fn grouped_by_parts(n: usize) -> impl Iterator<Item = (usize, Vec<Vec<usize>>)> {
+    let fewest = if n == 0 { 0 } else { 1 };
+
+    (fewest..(n + 1)).rev().map(move |k| {
+        let mut p = Restricted::into_parts(n, k);
+        let mut group = Vec::new();
+        while let Some(x) = p.next() {
+            group.push(x.to_vec());
+        }
+        (k, group)
+    })
+}
+
+/// Finds `0 + 1 + ... + (c - 1)`.
+fn triangle(c: usize) -> usize {
+    if c == 0 { 0 } else { c.saturating_mul(c - 1) / 2 }
+}
+
+/// Finds the largest `c` with `p + (p + 1) + ... + (p + c - 1) <= r`.
+fn distinct_len(r: usize, p: usize) -> usize {
+    let sum = |c: usize| c.saturating_mul(p).saturating_add(triangle(c));
+
+    // The answer is at most r / p, so a binary search is quick, and
+    // avoids needing floating point square roots.
+    let mut lo = 0;
+    let mut hi = r / p;
+    while lo < hi {
+        let mid = hi - (hi - lo) / 2;
+        if sum(mid) <= r {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+
+    lo
+}
+
+#[test]
+fn max_part() {
+    //! Compares counts against the restricted partition function.
+
+    let n = 30;
+
+    // table[i][m] is the number of partitions of i into parts <= m.
+    let mut table = vec![vec![0usize; n + 1]; n + 1];
+    table[0] = vec![1; n + 1];
+    for i in 1..(n + 1) {
+        for m in 1..(n + 1) {
+            table[i][m] = table[i][m - 1] + if m <= i { table[i - m][m] } else { 0 };
+        }
+    }
+
+    for (i, row) in table.iter().enumerate() {
+        for m in 0..(n + 3) {
+            let all = collect!(Restricted::with_max_part(i, m));
+
+            for x in &all {
+                assert_eq!(x.iter().cloned().sum::<usize>(), i);
+                assert!(x.iter().all(|&y| y <= m));
+            }
+
+            assert_eq!(all.len(), row[m.min(n)]);
+        }
+    }
+}
+
+#[test]
+fn min_part() {
+    //! Compares counts against the recurrence for partitions with a
+    //! smallest allowed part.
+
+    let n = 30;
+
+    // table[i][m] is the number of partitions of i into parts >= m.
+    let mut table = vec![vec![0usize; n + 2]; n + 1];
+    table[0] = vec![1; n + 2];
+    for i in 1..(n + 1) {
+        for m in (1..(n + 1)).rev() {
+            table[i][m] = table[i][m + 1] + if m <= i { table[i - m][m] } else { 0 };
+        }
+    }
+
+    for (i, row) in table.iter().enumerate() {
+        for (m, &c) in row.iter().enumerate().skip(1) {
+            let all = collect!(Restricted::min_part(i, m));
+
+            for x in &all {
+                assert_eq!(x.iter().cloned().sum::<usize>(), i);
+                assert!(x.iter().all(|&y| y >= m));
+            }
+
+            assert_eq!(all.len(), c);
+        }
+    }
+
+    let mut expected = Vec::new();
+    ::Partitions::new(12).for_each(|x| expected.push(x.to_vec()));
+    assert_eq!(collect!(Restricted::min_part(12, 1)), expected);
+    assert_eq!(collect!(Restricted::min_part(12, 0)), expected);
+    assert!(collect!(Restricted::min_part(5, 6)).is_empty());
+    assert_eq!(collect!(Restricted::min_part(0, 6)).len(), 1);
+}
+
+#[test]
+fn into_parts() {
+    //! Checks that the partitions into each number of parts add up to
+    //! all of the partitions.
+
+    for n in 0..30 {
+        let mut total = 0;
+
+        for k in 0..(n + 3) {
+            let all = collect!(Restricted::into_parts(n, k));
+
+            for x in &all {
+                assert_eq!(x.iter().cloned().sum::<usize>(), n);
+                assert_eq!(x.len(), k);
+            }
+
+            if k > n || (k == 0 && n > 0) {
+                assert!(all.is_empty());
+            }
+
+            total += all.len();
+        }
+
+        let mut p = ::Partitions::new(n);
+        let mut c = 0;
+        while p.next().is_some() {
+            c += 1;
+        }
+
+        assert_eq!(total, c);
+    }
+}
+
+#[test]
+fn largest_exactly() {
+    //! Compares against filtering, and checks that the counts for each
+    //! largest part add up to all of the partitions.
+
+    for n in 0..25 {
+        let mut total = 0;
+
+        for m in 0..(n + 2) {
+            let mut expected = Vec::new();
+            ::Partitions::new(n).for_each(|x| {
+                if x.last().cloned().unwrap_or(0) == m {
+                    expected.push(x.to_vec());
+                }
+            });
+
+            let actual = collect!(LargestExactly::new(n, m));
+
+            assert_eq!(actual, expected);
+            total += actual.len();
+        }
+
+        assert_eq!(total as u128, ::count(n));
+    }
+
+    assert_eq!(LargestExactly::new(5, 5).next(), Some(&[5][..]));
+    assert_eq!(LargestExactly::new(5, 6).next(), None);
+}
+
+#[test]
+fn grouped() {
+    //! Checks that the groups are in order, and that flattening them
+    //! gives every partition once.
+
+    for n in 0..25 {
+        let mut expected: Vec<Vec<usize>> = ::Partitions::new(n).into_iter().collect();
+        let mut all = Vec::new();
+        let mut last = n + 1;
+
+        for (k, group) in grouped_by_parts(n) {
+            assert_eq!(k + 1, last);
+            assert!(group.iter().all(|x| x.len() == k));
+            last = k;
+            all.extend(group);
+        }
+
+        all.sort();
+        expected.sort();
+        assert_eq!(all, expected);
+    }
+
+    assert_eq!(grouped_by_parts(0).collect::<Vec<_>>(), [(0, vec![vec![]])]);
+}
+
+#[test]
+fn at_most_parts() {
+    //! Checks the counts against the exact numbers of parts, and
+    //! against the conjugate restriction on the largest part.
+
+    for n in 0..25 {
+        let mut below = 0;
+
+        for k in 0..(n + 2) {
+            let all = collect!(Restricted::at_most_parts(n, k));
+            assert!(all.iter().all(|x| x.len() <= k));
+
+            let exact = collect!(Restricted::into_parts(n, k)).len();
+            assert_eq!(all.len(), below + exact);
+            assert_eq!(all.len(), collect!(Restricted::with_max_part(n, k)).len());
+
+            below = all.len();
+        }
+
+        assert_eq!(below, ::count(n) as usize);
+    }
+
+    assert!(collect!(Restricted::at_most_parts(3, 0)).is_empty());
+    assert_eq!(collect!(Restricted::at_most_parts(0, 0)).len(), 1);
+}
+
+#[test]
+fn distinct() {
+    //! Checks the counts against A000009, and that the partitions are
+    //! exactly the distinct ones from the full stream.
+
+    let tests: &[usize] = &[
+        1, 1, 1, 2, 2, 3, 4, 5, 6, 8, 10,
+        12, 15, 18, 22, 27, 32, 38, 46, 54, 64,
+        76, 89, 104, 122, 142, 165, 192, 222, 256, 296,
+    ];
+
+    for (n, &c) in tests.iter().enumerate() {
+        let mut expected = Vec::new();
+        let mut p = ::Partitions::new(n);
+        while let Some(x) = p.next() {
+            if x.windows(2).all(|w| w[0] < w[1]) {
+                expected.push(x.to_vec());
+            }
+        }
+
+        let all = collect!(Restricted::distinct(n));
+        assert_eq!(all.len(), c);
+        assert_eq!(all, expected);
+    }
+
+    assert_eq!(collect!(Restricted::distinct(0)), vec![Vec::<usize>::new()]);
+    assert_eq!(collect!(Restricted::distinct(1)), vec![vec![1]]);
+}
+
+#[test]
+fn distinct_min() {
+    //! Checks a small case by hand, and compares against filtering the
+    //! distinct partitions.
+
+    assert_eq!(
+        collect!(Restricted::distinct_min(10, 3)),
+        [&[3, 7][..], &[4, 6], &[10]],
+    );
+    assert_eq!(collect!(Restricted::distinct_min(6, 4)), [[6]]);
+    assert!(collect!(Restricted::distinct_min(6, 7)).is_empty());
+
+    for n in 0..30 {
+        for m in 0..(n + 2) {
+            let mut expected = collect!(Restricted::distinct(n));
+            expected.retain(|x| x.iter().all(|&y| y >= m));
+
+            assert_eq!(collect!(Restricted::distinct_min(n, m)), expected);
+            let mut p = ::Partitions::builder(n).min_part(m).distinct().build();
+            assert_eq!(collect!(p), expected);
+        }
+    }
+}
+
+#[test]
+fn distinct_into_parts() {
+    //! Checks a small case by hand, and that the partitions are right
+    //! and there are as many as `count_distinct_k` says.
+
+    assert_eq!(
+        collect!(Restricted::distinct_into_parts(10, 3)),
+        [&[1, 2, 7][..], &[1, 3, 6], &[1, 4, 5], &[2, 3, 5]],
+    );
+    assert_eq!(collect!(Restricted::distinct_into_parts(0, 0)), [Vec::<usize>::new()]);
+    assert!(collect!(Restricted::distinct_into_parts(5, 3)).is_empty());
+
+    for n in 0..30 {
+        for k in 0..(n + 2) {
+            let all = collect!(Restricted::distinct_into_parts(n, k));
+            for x in &all {
+                assert_eq!(x.len(), k);
+                assert_eq!(x.iter().sum::<usize>(), n);
+                assert!(x.windows(2).all(|w| w[0] < w[1]));
+            }
+
+            assert_eq!(all.len() as u128, ::count_distinct_k(n, k));
+        }
+    }
+}
+
+#[test]
+fn boxed() {
+    //! Compares against the coefficients of the Gaussian binomials.
+
+    for r in 0..7 {
+        for c in 0..7 {
+            // Work out `[r + c choose c]_q` as a product of quotients,
+            // each of which divides exactly.
+            let mut g = vec![1i64];
+            for i in 1..(c + 1) {
+                let a = r + i;
+                g.resize(g.len() + a, 0);
+                for j in (a..g.len()).rev() {
+                    g[j] -= g[j - a];
+                }
+                for j in i..g.len() {
+                    g[j] += g[j - i];
+                }
+                g.truncate(g.len() - i);
+            }
+
+            assert_eq!(g.len(), r * c + 1);
+            for (n, &x) in g.iter().enumerate() {
+                assert_eq!(collect!(Restricted::boxed(n, r, c)).len() as i64, x);
+            }
+            assert!(collect!(Restricted::boxed(r * c + 1, r, c)).is_empty());
+        }
+    }
+}
+
+#[test]
+fn builder() {
+    //! Compares combinations of restrictions against filtering.
+
+    for n in 0..20 {
+        let mut all = Vec::new();
+        ::Partitions::new(n).for_each(|x| all.push(x.to_vec()));
+
+        for lo in 0..5 {
+            for hi in 0..5 {
+                for m in 0..8 {
+                    for k in 0..8 {
+                        let mut expected = all.clone();
+                        expected.retain(|x| {
+                            x.iter().all(|&y| y >= m && y <= k)
+                                && x.len() >= lo && x.len() <= hi
+                        });
+
+                        let mut p = Builder::new(n)
+                            .max_part(k)
+                            .parts_between(lo, hi)
+                            .min_part(m)
+                            .build();
+                        assert_eq!(collect!(p), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    let tighter = collect!(Builder::new(10).max_part(5).max_part(3).build());
+    assert_eq!(tighter, collect!(Restricted::with_max_part(10, 3)));
+
+    assert!(collect!(Builder::new(10).min_part(5).max_part(3).build()).is_empty());
+    assert!(collect!(Builder::new(10).parts_between(4, 2).build()).is_empty());
+}
+
+#[test]
+fn max_part_edges() {
+    //! Checks the unconstrained and degenerate cases.
+
+    for n in 0..20 {
+        let expected = collect!(::Partitions::new(n));
+
+        assert_eq!(collect!(Restricted::with_max_part(n, n)), expected);
+        assert_eq!(collect!(Restricted::with_max_part(n, n + 5)), expected);
+    }
+
+    assert!(collect!(Restricted::with_max_part(5, 0)).is_empty());
+    assert_eq!(collect!(Restricted::with_max_part(0, 0)), vec![Vec::<usize>::new()]);
+}
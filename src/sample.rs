@@ -0,0 +1,92 @@
+//! Sampling partitions uniformly at random.
+
+use alloc::vec::Vec;
+use rand::{Rng, RngExt};
+
+use count::table;
+
+/// The largest `n` that `sample` can handle.
+pub const MAX_SAMPLE: usize = 1249;
+
+/// Picks a partition of `n` uniformly at random.
+///
+/// This is the method described by Nijenhuis and Wilf, which only
+/// needs the number of partitions of each integer up to `n`, rather
+/// than all of the partitions themselves. The parts are in ascending
+/// order.
+///
+/// # Panics
+///
+/// Panics if `n > MAX_SAMPLE`, since the probabilities involved would
+/// overflow.
+pub fn sample<R: Rng + ?Sized>(n: usize, rng: &mut R) -> Vec<usize> {
+    assert!(n <= MAX_SAMPLE, "can't sample partitions of {}", n);
+
+    let p = table(n);
+    let mut out = Vec::new();
+    let mut m = n;
+
+    while m > 0 {
+        // Each pair (d, j) is picked with probability proportional to
+        // d * p(m - j * d), and these weights add up to m * p(m).
+        let mut z = rng.random_range(0..(m as u128 * p[m]));
+
+        'pick: for d in 1..(m + 1) {
+            for j in 1..(m / d + 1) {
+                let w = d as u128 * p[m - j * d];
+                if z < w {
+                    out.extend((0..j).map(|_| d));
+                    m -= j * d;
+                    break 'pick;
+                }
+                z -= w;
+            }
+        }
+    }
+
+    out.sort();
+    out
+}
+
+#[test]
+fn uniform() {
+    //! Runs a chi-squared test on the partitions of a small number.
+
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    let n = 6;
+    let mut all = Vec::new();
+    ::Partitions::new(n).for_each(|x| all.push(x.to_vec()));
+
+    let trials = 2000 * all.len();
+    let mut seen = vec![0usize; all.len()];
+    let mut rng = SmallRng::seed_from_u64(0x5eed);
+
+    for _ in 0..trials {
+        let x = sample(n, &mut rng);
+        seen[all.iter().position(|y| *y == x).unwrap()] += 1;
+    }
+
+    let expected = (trials / all.len()) as f64;
+    let chi: f64 = seen.iter()
+        .map(|&c| (c as f64 - expected) * (c as f64 - expected) / expected)
+        .sum();
+
+    // The critical value for 10 degrees of freedom at p = 0.001.
+    assert!(chi < 29.59, "chi-squared statistic was {}", chi);
+}
+
+#[test]
+fn largest() {
+    //! Checks that the largest allowed sample doesn't overflow.
+
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    let mut rng = SmallRng::seed_from_u64(1);
+    let x = sample(MAX_SAMPLE, &mut rng);
+    assert_eq!(x.iter().cloned().sum::<usize>(), MAX_SAMPLE);
+    assert!(x.windows(2).all(|w| w[0] <= w[1]));
+    assert!(sample(0, &mut rng).is_empty());
+}
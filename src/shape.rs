@@ -0,0 +1,365 @@
+//! Functions on the shapes of individual partitions.
+//!
+//! These all take partitions with their parts in ascending order, the
+//! way that the iterators produce them.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Finds the conjugate of a partition, which is what you get by
+/// transposing its Young diagram.
+///
+/// The parts of the partition should be in ascending order, and so
+/// will the parts of its conjugate.
+#[inline]
+pub fn conjugate(partition: &[usize]) -> Vec<usize> {
+    let mut out = Vec::new();
+    conjugate_into(partition, &mut out);
+    out
+}
+
+/// Finds the conjugate of a partition, like `conjugate`, but writes it
+/// into an existing vector to avoid allocating.
+pub fn conjugate_into(partition: &[usize], out: &mut Vec<usize>) {
+    out.clear();
+
+    let len = partition.len();
+    let max = match partition.last() {
+        Some(&x) => x,
+        None => return,
+    };
+
+    out.reserve(max);
+
+    // The j-th part of the conjugate counts the parts that are at
+    // least j, and taking j downwards keeps the output ascending.
+    let mut i = len;
+    for j in (1..(max + 1)).rev() {
+        while i > 0 && partition[i - 1] >= j {
+            i -= 1;
+        }
+        out.push(len - i);
+    }
+}
+
+/// Finds the size of the Durfee square of a partition, which is the
+/// largest `d` such that at least `d` parts are at least `d`.
+pub fn durfee_square(partition: &[usize]) -> usize {
+    partition.iter()
+        .rev()
+        .enumerate()
+        .take_while(|&(i, &x)| x > i)
+        .count()
+}
+
+/// Finds Dyson's rank of a partition, which is its largest part minus
+/// its number of parts.
+///
+/// The largest part is the last one, since the parts are ascending,
+/// and the empty partition has a rank of zero.
+pub fn dyson_rank(partition: &[usize]) -> i64 {
+    match partition.last() {
+        Some(&x) => x as i64 - partition.len() as i64,
+        None => 0,
+    }
+}
+
+/// Finds the width of a partition, which is its number of parts.
+#[inline]
+pub fn width(partition: &[usize]) -> usize {
+    partition.len()
+}
+
+/// Finds the height of a partition, which is its largest part, or zero
+/// if it's empty.
+#[inline]
+pub fn height(partition: &[usize]) -> usize {
+    partition.last().cloned().unwrap_or(0)
+}
+
+/// Finds the weight of a partition, which is the sum of its parts.
+#[inline]
+pub fn weight(partition: &[usize]) -> usize {
+    partition.iter().sum()
+}
+
+/// Checks whether a partition is a staircase, with parts `1, 2, ..., k`
+/// for some `k`, which includes the empty partition.
+///
+/// Unlike the rest of this module, the parts can be in either order,
+/// so `k, k - 1, ..., 1` is a staircase too.
+#[inline]
+pub fn is_staircase(partition: &[usize]) -> bool {
+    let k = partition.len();
+    partition.iter().enumerate().all(|(i, &x)| x == i + 1)
+        || partition.iter().enumerate().all(|(i, &x)| x == k - i)
+}
+
+/// Finds the number of standard Young tableaux of a partition's shape,
+/// by the hook length formula.
+///
+/// Everything is done with prime factorizations, so the answer is
+/// exact whenever it fits, even though `n!` doesn't.
+///
+/// # Panics
+///
+/// Panics if the answer overflows a `u128`.
+pub fn num_sytx(partition: &[usize]) -> u128 {
+    let n: usize = partition.iter().sum();
+
+    // `e[m]` is the power of `m` in the answer's factorization, where
+    // the numbers that get factored further end up as zero.
+    let mut e: Vec<isize> = (0..(n + 1)).map(|m| if m >= 2 { 1 } else { 0 }).collect();
+    each_hook(partition, |h| {
+        if h >= 2 {
+            e[h] -= 1;
+        }
+    });
+
+    // Each composite `m = p * q` hands its power on to `p` and `q`, and
+    // going downwards means `m` has all of its power by then.
+    for m in (2..(n + 1)).rev() {
+        if let Some(p) = (2..m).take_while(|p| p * p <= m).find(|p| m / p * p == m) {
+            e[p] += e[m];
+            e[m / p] += e[m];
+            e[m] = 0;
+        }
+    }
+
+    let mut out = 1u128;
+    for (m, &k) in e.iter().enumerate().skip(2) {
+        debug_assert!(k >= 0, "the hook product doesn't divide n!");
+        for _ in 0..k {
+            out = out
+                .checked_mul(m as u128)
+                .unwrap_or_else(|| panic!("the number of tableaux overflows a u128"));
+        }
+    }
+
+    out
+}
+
+/// Finds the hook length of each cell of a partition's Young diagram.
+///
+/// The cells go along each row from the left, starting with the row
+/// of the largest part, so the empty partition gives nothing.
+pub fn hook_lengths(partition: &[usize]) -> Vec<usize> {
+    let mut out = Vec::with_capacity(partition.iter().sum());
+    each_hook(partition, |h| out.push(h));
+    out
+}
+
+/// Calls a closure with the hook length of each cell of a partition's
+/// Young diagram.
+fn each_hook<F: FnMut(usize)>(partition: &[usize], mut f: F) {
+    let len = partition.len();
+    let cols = conjugate(partition);
+    let width = cols.len();
+
+    // Row `i` from the top is the part `partition[len - 1 - i]`, and
+    // column `j` from the left is `cols[width - 1 - j]`.
+    for i in 0..len {
+        let row = partition[len - 1 - i];
+        for j in 0..row {
+            let col = cols[width - 1 - j];
+            f((row - j) + (col - i) - 1);
+        }
+    }
+}
+
+/// Draws the Ferrers diagram of a partition, using `*` for each cell.
+///
+/// The rows are drawn from the largest part down to the smallest, one
+/// per line, each followed by a newline.
+#[inline]
+pub fn ferrers(partition: &[usize]) -> String {
+    ferrers_with(partition, '*')
+}
+
+/// Draws the Ferrers diagram of a partition, like `ferrers`, but with
+/// some other character for each cell.
+#[inline]
+pub fn ferrers_with(partition: &[usize], cell: char) -> String {
+    let mut out = String::new();
+    ferrers_into(partition, cell, &mut out).unwrap();
+    out
+}
+
+/// Draws the Ferrers diagram of a partition, like `ferrers_with`, but
+/// writes it somewhere else.
+pub fn ferrers_into<W: fmt::Write>(partition: &[usize], cell: char, out: &mut W) -> fmt::Result {
+    for &x in partition.iter().rev() {
+        for _ in 0..x {
+            out.write_char(cell)?;
+        }
+        out.write_char('\n')?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn conjugate_involution() {
+    //! Checks that conjugating twice gives back the same partition.
+
+    ::Partitions::new(8).for_each(|x| {
+        let c = conjugate(x);
+        assert_eq!(c.iter().cloned().sum::<usize>(), 8);
+        assert!(c.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(conjugate(&c), x);
+    });
+
+    assert_eq!(conjugate(&[1, 2, 4]), [1, 1, 2, 3]);
+    assert!(conjugate(&[]).is_empty());
+}
+
+#[test]
+fn durfee() {
+    //! Checks that conjugation preserves the Durfee square.
+
+    ::Partitions::new(12).for_each(|x| {
+        assert_eq!(durfee_square(x), durfee_square(&conjugate(x)));
+    });
+
+    assert_eq!(durfee_square(&[]), 0);
+    assert_eq!(durfee_square(&[1]), 1);
+    assert_eq!(durfee_square(&[1, 3, 3, 5]), 3);
+}
+
+#[test]
+fn dyson() {
+    //! Checks some ranks, and Dyson's observation that the ranks of the
+    //! partitions of `5k + 4` are spread evenly modulo 5.
+
+    assert_eq!(dyson_rank(&[]), 0);
+    assert_eq!(dyson_rank(&[5]), 4);
+    assert_eq!(dyson_rank(&[1, 1, 1, 1, 1]), -4);
+    assert_eq!(dyson_rank(&[1, 2, 2]), -1);
+
+    ::Partitions::new(12).for_each(|x| {
+        assert_eq!(dyson_rank(x), -dyson_rank(&conjugate(x)));
+    });
+
+    let mut counts = [0; 5];
+    ::Partitions::new(24).for_each(|x| {
+        counts[dyson_rank(x).rem_euclid(5) as usize] += 1;
+    });
+    assert_eq!(counts, [315; 5]);
+}
+
+#[test]
+fn dimensions() {
+    //! Checks the width, height and weight of a small partition, and of
+    //! the empty one.
+
+    assert_eq!(width(&[1, 2, 4]), 3);
+    assert_eq!(height(&[1, 2, 4]), 4);
+    assert_eq!(weight(&[1, 2, 4]), 7);
+
+    assert_eq!(width(&[]), 0);
+    assert_eq!(height(&[]), 0);
+    assert_eq!(weight(&[]), 0);
+
+    // Conjugating swaps the width and the height.
+    for n in 0..15 {
+        ::Partitions::new(n).for_each(|x| {
+            let y = conjugate(x);
+            assert_eq!((width(x), height(x)), (height(&y), width(&y)));
+            assert_eq!(weight(x), n);
+        });
+    }
+}
+
+#[test]
+fn staircase() {
+    //! Checks some staircases and near misses in both orders, and that
+    //! there's one for each triangular number.
+
+    assert!(is_staircase(&[1, 2, 3]));
+    assert!(is_staircase(&[3, 2, 1]));
+    assert!(is_staircase(&[1]));
+    assert!(is_staircase(&[]));
+    assert!(!is_staircase(&[1, 3]));
+    assert!(!is_staircase(&[3, 1]));
+    assert!(!is_staircase(&[2, 2]));
+    assert!(!is_staircase(&[2, 3]));
+    assert!(!is_staircase(&[3, 2]));
+    assert!(!is_staircase(&[2, 1, 3]));
+
+    for n in 0..30 {
+        let mut c = 0;
+        ::Partitions::new(n).for_each(|x| {
+            if is_staircase(x) {
+                c += 1;
+            }
+        });
+
+        let triangular = (0..(n + 1)).any(|k| k * (k + 1) / 2 == n);
+        assert_eq!(c, if triangular { 1 } else { 0 });
+    }
+}
+
+#[test]
+fn tableaux() {
+    //! Checks some small shapes, and that the squares of the counts add
+    //! up to `n!`, as the RSK correspondence says.
+
+    assert_eq!(num_sytx(&[]), 1);
+    assert_eq!(num_sytx(&[1, 2]), 2);
+    assert_eq!(num_sytx(&[2, 3]), 5);
+    assert_eq!(num_sytx(&[3, 3, 3]), 42);
+
+    let mut factorial = 1u128;
+    for n in 0..11 {
+        if n > 0 {
+            factorial *= n as u128;
+        }
+
+        let mut sum = 0;
+        ::Partitions::new(n).for_each(|x| sum += num_sytx(x) * num_sytx(x));
+        assert_eq!(sum, factorial);
+    }
+
+    // 40! overflows, but the single-row count doesn't.
+    assert_eq!(num_sytx(&[40]), 1);
+    assert_eq!(num_sytx(&[1, 39]), 39);
+}
+
+#[test]
+fn hooks() {
+    //! Checks a small shape by hand, and that the product of the hooks
+    //! divides `n!` with the number of tableaux left over.
+
+    assert_eq!(hook_lengths(&[]), Vec::<usize>::new());
+    assert_eq!(hook_lengths(&[1, 2, 3]), [5, 3, 1, 3, 1, 1]);
+
+    let mut factorial = 1u128;
+    for n in 0..10 {
+        if n > 0 {
+            factorial *= n as u128;
+        }
+
+        ::Partitions::new(n).for_each(|x| {
+            let hooks = hook_lengths(x);
+            assert_eq!(hooks.len(), n);
+            assert!(hooks.iter().all(|&h| h > 0));
+
+            let product = hooks.iter().map(|&h| h as u128).product::<u128>();
+            assert_eq!(factorial % product, 0);
+            assert_eq!(factorial / product, num_sytx(x));
+        });
+    }
+}
+
+#[test]
+fn ferrers_shape() {
+    //! Checks the diagram of a small partition.
+
+    let d = ferrers(&[1, 2, 3]);
+    assert_eq!(d, "***\n**\n*\n");
+    assert_eq!(d.chars().filter(|&c| c == '*').count(), 6);
+
+    assert_eq!(ferrers_with(&[2, 2], '#'), "##\n##\n");
+    assert_eq!(ferrers(&[]), "");
+}
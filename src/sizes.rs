@@ -0,0 +1,237 @@
+//! Partitions with only a few different sizes of part.
+
+use alloc::vec::Vec;
+
+/// Iterates over the partitions of a given integer that use at most a
+/// given number of different part sizes.
+///
+/// This is made by `Partitions::distinct_sizes_at_most`. The partitions
+/// come out in the same order as they would from `Partitions`, with
+/// the parts of each one in ascending order, and runs of parts that
+/// would need another size are never started.
+pub struct DistinctSizes {
+    a: Vec<usize>,
+    /// Each size that's in use, and how many times.
+    runs: Vec<(usize, usize)>,
+    n: usize,
+    d: usize,
+    state: State,
+}
+
+enum State {
+    Start,
+    Running,
+    Done,
+}
+
+impl DistinctSizes {
+    #[inline]
+    pub(crate) fn new(n: usize, d: usize) -> DistinctSizes {
+        DistinctSizes {
+            a: Vec::new(),
+            runs: Vec::new(),
+            n,
+            d,
+            state: State::Start,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        match self.state {
+            State::Start => {
+                self.state = State::Running;
+                if !self.fill(self.n, 1) {
+                    self.state = State::Done;
+                    return None;
+                }
+            },
+            State::Running => {
+                let mut r = 0;
+
+                loop {
+                    let (s, m) = match self.runs.pop() {
+                        Some(x) => x,
+                        None => {
+                            self.state = State::Done;
+                            return None;
+                        },
+                    };
+
+                    let len = self.a.len();
+                    self.a.truncate(len - m);
+                    r += s * m;
+
+                    // The next partition either has a shorter run of
+                    // the same size, or switches to a bigger size.
+                    let last = self.runs.len() + 1 == self.d;
+                    if !last && r > 2 * s {
+                        let m = (m - 1).min((r - s - 1) / s);
+                        if m > 0 {
+                            self.push(s, m);
+                            self.fill(r - s * m, s + 1);
+                            break;
+                        }
+                    }
+
+                    if self.fill(r, s + 1) {
+                        break;
+                    }
+                }
+            },
+            State::Done => return None,
+        }
+
+        Some(&self.a)
+    }
+
+    /// Appends the first ascending run of parts from `p` upwards that
+    /// sums to `r`, and returns whether there was one.
+    fn fill(&mut self, mut r: usize, mut p: usize) -> bool {
+        while r > 0 {
+            if self.runs.len() == self.d || p > r {
+                return false;
+            }
+
+            // Using `x` for the rest always works if it divides it, but
+            // otherwise what's left over has to be bigger than `x` and
+            // there has to be another size for it.
+            let last = self.runs.len() + 1 == self.d;
+            let (x, m) = (p..(r + 1))
+                .filter_map(|x| {
+                    if r / x * x == r {
+                        Some((x, r / x))
+                    } else if !last && r > 2 * x {
+                        Some((x, (r - x - 1) / x))
+                    } else {
+                        None
+                    }
+                })
+                .next()
+                .unwrap();
+
+            self.push(x, m);
+            r -= x * m;
+            p = x + 1;
+        }
+
+        true
+    }
+
+    fn push(&mut self, x: usize, m: usize) {
+        self.runs.push((x, m));
+        for _ in 0..m {
+            self.a.push(x);
+        }
+    }
+}
+
+/// Iterates over the partitions of a given integer that use at most a
+/// given number of different part sizes, as `(part, multiplicity)`
+/// pairs.
+///
+/// This is made by `Partitions::limited_kinds`. It's the same as
+/// `DistinctSizes`, but the runs it keeps track of anyway are yielded
+/// instead of the parts, with the sizes in ascending order.
+pub struct LimitedKinds {
+    inner: DistinctSizes,
+}
+
+impl LimitedKinds {
+    #[inline]
+    pub(crate) fn new(n: usize, d: usize) -> LimitedKinds {
+        LimitedKinds {
+            inner: DistinctSizes::new(n, d),
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[(usize, usize)]> {
+        self.inner.next()?;
+        Some(&self.inner.runs)
+    }
+}
+
+#[test]
+fn one_size() {
+    //! Checks that using one size gives a partition for each divisor.
+
+    for n in 1..50 {
+        let mut expected = Vec::new();
+        for m in 1..(n + 1) {
+            if n % m == 0 {
+                expected.push(vec![m; n / m]);
+            }
+        }
+
+        assert_eq!(collect!(DistinctSizes::new(n, 1)), expected);
+    }
+
+    assert_eq!(collect!(DistinctSizes::new(0, 0)), vec![Vec::<usize>::new()]);
+    assert!(collect!(DistinctSizes::new(5, 0)).is_empty());
+}
+
+#[test]
+fn filtered() {
+    //! Compares against filtering all of the partitions.
+
+    for n in 0..25 {
+        for d in 0..(n + 2) {
+            let mut expected = Vec::new();
+            ::Partitions::new(n).for_each(|x| {
+                let mut sizes = x.to_vec();
+                sizes.dedup();
+                if sizes.len() <= d {
+                    expected.push(x.to_vec());
+                }
+            });
+
+            assert_eq!(collect!(DistinctSizes::new(n, d)), expected);
+        }
+    }
+}
+
+#[test]
+fn limited_kinds() {
+    //! Checks that expanding the runs gives the same partitions as
+    //! `DistinctSizes`, and that compressing them again is stable.
+
+    for n in 0..25 {
+        for d in 0..(n + 2) {
+            let expected = collect!(DistinctSizes::new(n, d));
+            let mut actual = Vec::new();
+
+            let mut p = LimitedKinds::new(n, d);
+            while let Some(x) = p.next() {
+                assert!(x.len() <= d);
+                assert!(x.windows(2).all(|w| w[0].0 < w[1].0));
+
+                let mut flat = Vec::new();
+                for &(part, count) in x {
+                    assert!(count > 0);
+                    flat.extend((0..count).map(|_| part));
+                }
+
+                let mut runs: Vec<(usize, usize)> = Vec::new();
+                for &y in &flat {
+                    match runs.last_mut() {
+                        Some(&mut (part, ref mut count)) if part == y => *count += 1,
+                        _ => runs.push((y, 1)),
+                    }
+                }
+                assert_eq!(runs, x);
+
+                actual.push(flat);
+            }
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    assert_eq!(LimitedKinds::new(0, 0).next(), Some(&[][..]));
+    assert_eq!(LimitedKinds::new(3, 0).next(), None);
+}
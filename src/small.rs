@@ -0,0 +1,46 @@
+//! Partitions as small vectors.
+
+use smallvec::SmallVec;
+
+use Partitions;
+
+/// Iterates over partitions as `SmallVec`s, which keep up to 16 parts
+/// inline and only go on the heap for longer partitions.
+///
+/// This is made by `Partitions::small`, and yields the same partitions
+/// in the same order as `Partitions`.
+pub struct PartitionsSmall {
+    inner: Partitions,
+}
+
+impl PartitionsSmall {
+    #[inline]
+    pub(crate) fn new(n: usize) -> PartitionsSmall {
+        PartitionsSmall { inner: Partitions::new(n) }
+    }
+}
+
+impl Iterator for PartitionsSmall {
+    type Item = SmallVec<[usize; 16]>;
+
+    #[inline]
+    fn next(&mut self) -> Option<SmallVec<[usize; 16]>> {
+        self.inner.next().map(SmallVec::from_slice)
+    }
+}
+
+#[test]
+fn same() {
+    //! Compares against the standard iterator.
+
+    let mut p = Partitions::new(30);
+    let mut q = PartitionsSmall::new(30);
+
+    while let Some(x) = p.next() {
+        let y = q.next().unwrap();
+        assert_eq!(&y[..], x);
+        assert_eq!(y.spilled(), x.len() > 16);
+    }
+
+    assert!(q.next().is_none());
+}
@@ -0,0 +1,88 @@
+//! Enumerating partitions as an asynchronous stream.
+
+use alloc::vec::Vec;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_core::Stream;
+
+use Partitions;
+
+/// How many partitions the stream yields before giving the executor a
+/// chance to run something else.
+const BUDGET: usize = 1024;
+
+/// Makes a stream of the partitions of `n`, in the same order as
+/// `Partitions::new(n)`.
+#[inline]
+pub fn partition_stream(n: usize) -> PartitionStream {
+    PartitionStream {
+        inner: Partitions::new(n),
+        budget: BUDGET,
+    }
+}
+
+/// A stream of partitions.
+///
+/// This is made by `partition_stream`. Generating partitions never
+/// blocks, so every so often the stream returns `Poll::Pending` and
+/// immediately wakes itself up again, so that a long run of them
+/// doesn't starve the other tasks on the executor.
+pub struct PartitionStream {
+    inner: Partitions,
+    budget: usize,
+}
+
+impl Stream for PartitionStream {
+    type Item = Vec<usize>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Vec<usize>>> {
+        let this = self.get_mut();
+
+        if this.budget == 0 {
+            this.budget = BUDGET;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        this.budget -= 1;
+        Poll::Ready(this.inner.next().map(|x| x.to_vec()))
+    }
+}
+
+#[test]
+fn sequential() {
+    //! Compares against the synchronous iterator.
+
+    use futures::executor::block_on;
+    use futures::StreamExt;
+
+    for n in 0..30 {
+        let mut expected = Vec::new();
+        Partitions::new(n).for_each(|x| expected.push(x.to_vec()));
+
+        let actual: Vec<Vec<usize>> = block_on(partition_stream(n).collect());
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn cooperative() {
+    //! Checks that the stream gives way to the executor now and then.
+
+    use futures::task::noop_waker;
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut s = partition_stream(30);
+
+    let mut pending = 0;
+    loop {
+        match Pin::new(&mut s).poll_next(&mut cx) {
+            Poll::Ready(Some(_)) => {},
+            Poll::Ready(None) => break,
+            Poll::Pending => pending += 1,
+        }
+    }
+
+    assert_eq!(pending, 5604 / BUDGET);
+}
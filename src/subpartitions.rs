@@ -0,0 +1,130 @@
+//! Partitions that fit inside another one.
+
+use alloc::vec::Vec;
+
+/// Iterates over the partitions whose Young diagrams fit inside a given
+/// one's, of any size, including the empty partition.
+///
+/// A partition fits inside `lambda` if its `i`th largest part is never
+/// bigger than the `i`th largest part of `lambda`. The parts of each
+/// partition are in ascending order, and the partitions come out in
+/// lexicographic order of their parts from largest to smallest, so the
+/// empty partition is first and `lambda` itself is last.
+pub struct SubPartitions {
+    /// The parts of `lambda`, in descending order.
+    lambda: Vec<usize>,
+    /// The parts of the current partition, in descending order and
+    /// padded with zeros to the length of `lambda`.
+    mu: Vec<usize>,
+    out: Vec<usize>,
+    state: State,
+}
+
+enum State {
+    Start,
+    Running,
+    Done,
+}
+
+impl SubPartitions {
+    /// Makes an iterator over the partitions that fit inside `lambda`,
+    /// which can have its parts in any order.
+    pub fn new(lambda: &[usize]) -> SubPartitions {
+        let mut lambda: Vec<usize> = lambda.iter().cloned().filter(|&x| x > 0).collect();
+        lambda.sort_by(|x, y| y.cmp(x));
+
+        SubPartitions {
+            mu: vec![0; lambda.len()],
+            out: Vec::with_capacity(lambda.len()),
+            lambda,
+            state: State::Start,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        match self.state {
+            State::Start => self.state = State::Running,
+            State::Running => {
+                // The last part that can grow does, and everything after
+                // it goes back to zero, which always still fits.
+                let mu = &self.mu;
+                let lambda = &self.lambda;
+                let j = (0..mu.len())
+                    .rev()
+                    .find(|&j| mu[j] < lambda[j] && (j == 0 || mu[j] < mu[j - 1]));
+
+                match j {
+                    Some(j) => {
+                        self.mu[j] += 1;
+                        for x in &mut self.mu[(j + 1)..] {
+                            *x = 0;
+                        }
+                    },
+                    None => {
+                        self.state = State::Done;
+                        return None;
+                    },
+                }
+            },
+            State::Done => return None,
+        }
+
+        self.out.clear();
+        self.out.extend(self.mu.iter().rev().cloned().filter(|&x| x > 0));
+        Some(&self.out)
+    }
+}
+
+#[test]
+fn small() {
+    //! Checks some small shapes by hand.
+
+    assert_eq!(
+        collect!(SubPartitions::new(&[2, 1])),
+        [&[][..], &[1], &[1, 1], &[2], &[1, 2]],
+    );
+    assert_eq!(collect!(SubPartitions::new(&[])), [Vec::<usize>::new()]);
+    assert_eq!(collect!(SubPartitions::new(&[0, 3, 0])), [&[][..], &[1], &[2], &[3]]);
+}
+
+#[test]
+fn filtered() {
+    //! Compares against filtering all of the small enough partitions,
+    //! and checks that a rectangle has a binomial number of them.
+
+    let shapes: &[&[usize]] = &[
+        &[1], &[1, 1, 1], &[3, 1], &[1, 2, 2], &[4, 3, 1, 1], &[2, 5, 5, 3],
+    ];
+
+    for lambda in shapes {
+        let mut sorted = lambda.to_vec();
+        sorted.sort_by(|x, y| y.cmp(x));
+
+        let mut expected = Vec::new();
+        for m in 0..(sorted.iter().sum::<usize>() + 1) {
+            ::Partitions::new(m).for_each(|x| {
+                let fits = x.iter().rev().zip(&sorted).all(|(a, b)| a <= b);
+                if x.len() <= sorted.len() && fits {
+                    expected.push(x.to_vec());
+                }
+            });
+        }
+
+        let mut actual = collect!(SubPartitions::new(lambda));
+        assert_eq!(actual.len(), expected.len());
+        actual.sort();
+        expected.sort();
+        assert_eq!(actual, expected);
+    }
+
+    // The partitions inside an `a` by `b` box number `C(a + b, a)`.
+    let mut c = 0;
+    let mut p = SubPartitions::new(&[4; 3]);
+    while p.next().is_some() {
+        c += 1;
+    }
+    assert_eq!(c, 35);
+}
@@ -0,0 +1,77 @@
+//! Finding the best few partitions without keeping all of them.
+
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use Partitions;
+
+/// A partition in the heap, with its key and where it came in the
+/// stream, ordered so that the worst one is on top.
+type Entry<K> = Reverse<(K, Reverse<usize>, Vec<usize>)>;
+
+/// Finds the `k` partitions of `n` with the largest keys, from largest
+/// to smallest, with ties going to whichever partition comes first.
+///
+/// Only the `k` best so far are kept, in a heap, and a partition is
+/// only copied out if it beats the worst of them, whose buffer it
+/// then takes over.
+pub fn top_k_by<K, F>(n: usize, k: usize, mut key: F) -> Vec<Vec<usize>>
+where
+    K: Ord,
+    F: FnMut(&[usize]) -> K,
+{
+    if k == 0 {
+        return Vec::new();
+    }
+
+    // Later partitions are worse than earlier ones with the same key.
+    let mut heap: BinaryHeap<Entry<K>> = BinaryHeap::new();
+
+    let mut p = Partitions::new(n);
+    let mut i = 0;
+    while let Some(x) = p.next() {
+        let y = key(x);
+
+        let mut buf = if heap.len() < k {
+            Vec::with_capacity(x.len())
+        } else if heap.peek().unwrap().0 .0 < y {
+            heap.pop().unwrap().0 .2
+        } else {
+            i += 1;
+            continue;
+        };
+
+        buf.clear();
+        buf.extend_from_slice(x);
+        heap.push(Reverse((y, Reverse(i), buf)));
+        i += 1;
+    }
+
+    heap.into_sorted_vec().into_iter().map(|Reverse((_, _, x))| x).collect()
+}
+
+#[test]
+fn naive() {
+    //! Compares against sorting all of the partitions.
+
+    fn distinct(x: &[usize]) -> usize {
+        let mut y = x.to_vec();
+        y.dedup();
+        y.len()
+    }
+
+    for n in 0..20 {
+        let mut all: Vec<Vec<usize>> = Partitions::new(n).into_iter().collect();
+        all.sort_by_key(|x| Reverse(distinct(x)));
+
+        for k in 0..12 {
+            let expected: Vec<Vec<usize>> = all.iter().take(k).cloned().collect();
+            assert_eq!(top_k_by(n, k, distinct), expected);
+        }
+
+        assert_eq!(top_k_by(n, all.len() + 1, distinct), all);
+    }
+
+    assert_eq!(top_k_by(6, 2, |x| x.len()), [&[1; 6][..], &[1, 1, 1, 1, 2]]);
+}
@@ -0,0 +1,50 @@
+//! Writing partitions out as text.
+
+use std::io::{self, Write};
+use std::vec::Vec;
+
+use Partitions;
+
+/// Writes every partition of `n`, one per line, with the parts
+/// separated by `sep`.
+///
+/// The partitions are in the order that `Partitions` yields them, and
+/// each line is built up in a single reused buffer before it's
+/// written, so there's one `write_all` call per partition.
+pub fn write_all<W: Write + ?Sized>(n: usize, w: &mut W, sep: u8) -> io::Result<()> {
+    let mut p = Partitions::new(n);
+    let mut line = Vec::new();
+
+    while let Some(x) = p.next() {
+        line.clear();
+
+        for (i, y) in x.iter().enumerate() {
+            if i > 0 {
+                line.push(sep);
+            }
+            write!(line, "{}", y)?;
+        }
+
+        line.push(b'\n');
+        w.write_all(&line)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn four() {
+    //! Checks the output for a small number.
+
+    let mut out = Vec::new();
+    write_all(4, &mut out, b' ').unwrap();
+    assert_eq!(out, b"1 1 1 1\n1 1 2\n1 3\n2 2\n4\n");
+
+    let mut out = Vec::new();
+    write_all(4, &mut out, b',').unwrap();
+    assert_eq!(out, b"1,1,1,1\n1,1,2\n1,3\n2,2\n4\n");
+
+    let mut out = Vec::new();
+    write_all(0, &mut out, b' ').unwrap();
+    assert_eq!(out, b"\n");
+}